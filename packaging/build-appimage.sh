@@ -33,6 +33,12 @@ exec "${HERE}/archtoys-bin" "$@"
 SH
 chmod +x "${APPDIR}/usr/bin/archtoys"
 
+for po in "${ROOT_DIR}"/lang/*/LC_MESSAGES/color-picker.po; do
+  lang="$(basename "$(dirname "$(dirname "${po}")")")"
+  mkdir -p "${APPDIR}/usr/share/locale/${lang}/LC_MESSAGES"
+  msgfmt -o "${APPDIR}/usr/share/locale/${lang}/LC_MESSAGES/color-picker.mo" "${po}"
+done
+
 install -Dm644 "${ROOT_DIR}/packaging/archtoys.desktop" "${APPDIR}/usr/share/applications/archtoys.desktop"
 install -Dm644 "${ROOT_DIR}/packaging/archtoys-256.png" "${APPDIR}/usr/share/icons/hicolor/256x256/apps/archtoys.png"
 
@@ -1,62 +1,379 @@
 slint::include_modules!();
 
-use arboard::{Clipboard, SetExtLinux};
+use arboard::{Clipboard, ImageData};
 use device_query::{DeviceQuery, DeviceState, Keycode};
 use global_hotkey::hotkey::HotKey;
 use global_hotkey::GlobalHotKeyManager;
-use image::{GenericImageView, ImageFormat};
+use image::{DynamicImage, GenericImageView, ImageFormat, Rgba, RgbaImage};
+use inotify::{Inotify, WatchMask};
 use ksni::blocking::TrayMethods;
-use ksni::menu::StandardItem;
-use ksni::{Icon, MenuItem, Tray};
-use palette::{FromColor, Hsl, Hsv, IntoColor, Srgb};
+use ksni::menu::{CheckmarkItem, RadioGroup, RadioItem, StandardItem, SubMenu};
+use ksni::{Icon, MenuItem, ToolTip, Tray};
+use lcms2::{
+    CIExyY, CIExyYTRIPLE, InfoType, Intent, Locale, PixelFormat, Profile, ToneCurve, Transform,
+};
+use palette::convert::IntoColorUnclamped;
+use palette::{FromColor, Hsl, Hsv, IntoColor, Lab, LinSrgb, Oklab, Oklch, Srgb};
 use scrap::{Capturer, Display};
 use serde::{Deserialize, Serialize};
-use slint::{Color, LogicalPosition, ModelRc, VecModel};
-use std::cell::RefCell;
-use std::collections::HashMap;
+use slint::{
+    Color, Model, ModelRc, PhysicalPosition, Rgba8Pixel, SharedPixelBuffer, SharedString, VecModel,
+};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::ffi::OsStr;
 use std::fs;
-use std::io::ErrorKind;
-use std::path::PathBuf;
+use std::io::{Cursor, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::process::Command;
 use std::rc::Rc;
 use std::str::FromStr;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex, OnceLock};
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
 use std::thread;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use x11rb::connection::Connection as _;
-use x11rb::protocol::xproto::{ConnectionExt as _, EventMask, GrabMode, GrabStatus};
+use x11rb::protocol::randr::ConnectionExt as _;
+use x11rb::protocol::xproto::{AtomEnum, ConnectionExt as _, EventMask, GrabMode, GrabStatus};
 use x11rb::{CURRENT_TIME, NONE};
 use zbus::blocking::{Connection as ZbusConnection, Proxy as ZbusProxy};
-use zbus::zvariant::{OwnedObjectPath, OwnedValue, Value};
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Structure, Value};
 
 static PICKER_ACTIVE: AtomicBool = AtomicBool::new(false);
 static PICKER_CANCELLED: AtomicBool = AtomicBool::new(false);
-
-const OVERLAY_WIDTH: i32 = 140;
-const OVERLAY_HEIGHT: i32 = 44;
-const OVERLAY_OFFSET_X: i32 = 20;
-const OVERLAY_OFFSET_Y: i32 = 20;
+static CONFIG_WATCHER_ACTIVE: AtomicBool = AtomicBool::new(false);
+static POINT_WATCH_ACTIVE: AtomicBool = AtomicBool::new(false);
+static POINT_WATCH_STOP: AtomicBool = AtomicBool::new(false);
+static CLIPBOARD_OWNER: OnceLock<mpsc::Sender<ClipboardRequest>> = OnceLock::new();
+
+// Last config text read from or written to disk; lets the watcher tell our own
+// writes apart from edits made by other tools.
+static CONFIG_SYNCED_TEXT: Mutex<Option<String>> = Mutex::new(None);
+// Colors picked since the config was last saved, newest first; an external edit keeps them.
+static PENDING_PICKS: Mutex<Vec<(u8, u8, u8)>> = Mutex::new(Vec::new());
+
+const OVERLAY_DEFAULT_OFFSET: i32 = 20;
+const OVERLAY_DEFAULT_LOUPE_PIXELS: u32 = 11;
 const WINDOW_MIN_WIDTH: f64 = 480.0;
 const WINDOW_MIN_HEIGHT: f64 = 320.0;
 const WINDOW_MAX_WIDTH: f64 = 900.0;
 const WINDOW_MAX_HEIGHT: f64 = 620.0;
 const DEFAULT_HOTKEY_TEXT: &str = "Ctrl+Super+C";
+const PALETTE_MAX_COLORS: usize = 6;
+const GRADIENT_SAMPLES: usize = 32;
+const MIX_STEPS: usize = 7;
+const EDITOR_PLANE_WIDTH: u32 = 160;
+const EDITOR_PLANE_HEIGHT: u32 = 110;
+/// Chroma at the right edge of the OKLCH plane; covers every sRGB color.
+const OKLCH_MAX_CHROMA: f32 = 0.37;
+/// Largest per-channel error allowed when dropping gradient stops.
+const GRADIENT_TOLERANCE: f32 = 6.0;
+const PORTAL_PICK_SHORTCUT_ID: &str = "pick";
+const TRAY_RECENT_COLORS: usize = 8;
+const UNDO_LIMIT: usize = 100;
+const COMMAND_PALETTE_RESULTS: usize = 12;
+const THEME_FILE_NAME: &str = "theme.json";
+const APPEARANCE_NAMESPACE: &str = "org.freedesktop.appearance";
+const KDE_GENERAL_NAMESPACE: &str = "org.kde.kdeglobals.General";
+const NOTIFY_SWATCH_SIZE: i32 = 48;
+const CLIPBOARD_SWATCH_SIZE: usize = 64;
+const WATCH_GRAPH_SAMPLES: usize = 200;
+const WATCH_MAX_SAMPLES: usize = 100_000;
+const WATCH_DEFAULT_INTERVAL_MS: u64 = 100;
+const COMPARE_DEFAULT_TOLERANCE: f32 = 2.0;
+const NOTIFY_ACTION_OPEN: &str = "open-details";
+const NOTIFY_ACTION_COPY_RGB: &str = "copy-rgb";
+const NOTIFY_ACTION_SAVE_PALETTE: &str = "save-palette";
+const COPY_FORMATS: [ColorField; 10] = [
+    ColorField::Hex,
+    ColorField::Rgb,
+    ColorField::Hsl,
+    ColorField::Hsv,
+    ColorField::DisplayP3,
+    ColorField::Rec2020,
+    ColorField::LinearFloat,
+    ColorField::Float,
+    ColorField::Rgb16,
+    ColorField::Description,
+];
+const REC2020_ALPHA: f32 = 1.099_296_8;
+const REC2020_BETA: f32 = 0.018_053_97;
+
+/// Picked colors, newest first, shared with the hotkey, tray and picker threads.
+type HistoryStore = Arc<Mutex<Vec<(u8, u8, u8)>>>;
+
+type Mat3 = [[f32; 3]; 3];
+
+// CSS Color 4 conversion matrices between linear RGB spaces and CIE XYZ (D65).
+const IDENTITY_MAT3: Mat3 = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+const LINEAR_SRGB_TO_XYZ: Mat3 = [
+    [0.412_390_8, 0.357_584_33, 0.180_480_8],
+    [0.212_639, 0.715_168_7, 0.072_192_32],
+    [0.019_330_818, 0.119_194_78, 0.950_532_15],
+];
+const XYZ_TO_LINEAR_SRGB: Mat3 = [
+    [3.240_97, -1.537_383_2, -0.498_610_76],
+    [-0.969_243_65, 1.875_967_5, 0.041_555_06],
+    [0.055_630_08, -0.203_976_96, 1.056_971_5],
+];
+const DISPLAY_P3_TO_XYZ: Mat3 = [
+    [0.486_570_95, 0.265_667_7, 0.198_217_29],
+    [0.228_974_56, 0.691_738_5, 0.079_286_91],
+    [0.0, 0.045_113_38, 1.043_944_4],
+];
+const XYZ_TO_DISPLAY_P3: Mat3 = [
+    [2.493_497, -0.931_383_6, -0.402_710_78],
+    [-0.829_489, 1.762_664_1, 0.023_624_686],
+    [0.035_845_83, -0.076_172_39, 0.956_884_5],
+];
+const REC2020_TO_XYZ: Mat3 = [
+    [0.636_958, 0.144_616_9, 0.168_880_98],
+    [0.262_700_2, 0.677_998_1, 0.059_301_716],
+    [0.0, 0.028_072_693, 1.060_985_1],
+];
+const XYZ_TO_REC2020: Mat3 = [
+    [1.716_651_2, -0.355_670_78, -0.253_366_3],
+    [-0.666_684_3, 1.616_481_2, 0.015_768_546],
+    [0.017_639_857, -0.042_770_613, 0.942_103_1],
+];
+const A98_RGB_TO_XYZ: Mat3 = [
+    [0.576_669, 0.185_558_24, 0.188_228_65],
+    [0.297_344_98, 0.627_363_55, 0.075_291_46],
+    [0.027_031_362, 0.070_688_85, 0.991_337_54],
+];
+const XYZ_TO_A98_RGB: Mat3 = [
+    [2.041_588, -0.565_007, -0.344_731_35],
+    [-0.969_243_65, 1.875_967_5, 0.041_555_06],
+    [0.013_444_28, -0.118_362_39, 1.015_175],
+];
+
+/// CSS Color 4 named colors.
+const CSS_COLOR_NAMES: &[(&str, u32)] = &[
+    ("aliceblue", 0xF0F8FF),
+    ("antiquewhite", 0xFAEBD7),
+    ("aqua", 0x00FFFF),
+    ("aquamarine", 0x7FFFD4),
+    ("azure", 0xF0FFFF),
+    ("beige", 0xF5F5DC),
+    ("bisque", 0xFFE4C4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xFFEBCD),
+    ("blue", 0x0000FF),
+    ("blueviolet", 0x8A2BE2),
+    ("brown", 0xA52A2A),
+    ("burlywood", 0xDEB887),
+    ("cadetblue", 0x5F9EA0),
+    ("chartreuse", 0x7FFF00),
+    ("chocolate", 0xD2691E),
+    ("coral", 0xFF7F50),
+    ("cornflowerblue", 0x6495ED),
+    ("cornsilk", 0xFFF8DC),
+    ("crimson", 0xDC143C),
+    ("cyan", 0x00FFFF),
+    ("darkblue", 0x00008B),
+    ("darkcyan", 0x008B8B),
+    ("darkgoldenrod", 0xB8860B),
+    ("darkgray", 0xA9A9A9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xA9A9A9),
+    ("darkkhaki", 0xBDB76B),
+    ("darkmagenta", 0x8B008B),
+    ("darkolivegreen", 0x556B2F),
+    ("darkorange", 0xFF8C00),
+    ("darkorchid", 0x9932CC),
+    ("darkred", 0x8B0000),
+    ("darksalmon", 0xE9967A),
+    ("darkseagreen", 0x8FBC8F),
+    ("darkslateblue", 0x483D8B),
+    ("darkslategray", 0x2F4F4F),
+    ("darkslategrey", 0x2F4F4F),
+    ("darkturquoise", 0x00CED1),
+    ("darkviolet", 0x9400D3),
+    ("deeppink", 0xFF1493),
+    ("deepskyblue", 0x00BFFF),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1E90FF),
+    ("firebrick", 0xB22222),
+    ("floralwhite", 0xFFFAF0),
+    ("forestgreen", 0x228B22),
+    ("fuchsia", 0xFF00FF),
+    ("gainsboro", 0xDCDCDC),
+    ("ghostwhite", 0xF8F8FF),
+    ("gold", 0xFFD700),
+    ("goldenrod", 0xDAA520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xADFF2F),
+    ("grey", 0x808080),
+    ("honeydew", 0xF0FFF0),
+    ("hotpink", 0xFF69B4),
+    ("indianred", 0xCD5C5C),
+    ("indigo", 0x4B0082),
+    ("ivory", 0xFFFFF0),
+    ("khaki", 0xF0E68C),
+    ("lavender", 0xE6E6FA),
+    ("lavenderblush", 0xFFF0F5),
+    ("lawngreen", 0x7CFC00),
+    ("lemonchiffon", 0xFFFACD),
+    ("lightblue", 0xADD8E6),
+    ("lightcoral", 0xF08080),
+    ("lightcyan", 0xE0FFFF),
+    ("lightgoldenrodyellow", 0xFAFAD2),
+    ("lightgray", 0xD3D3D3),
+    ("lightgreen", 0x90EE90),
+    ("lightgrey", 0xD3D3D3),
+    ("lightpink", 0xFFB6C1),
+    ("lightsalmon", 0xFFA07A),
+    ("lightseagreen", 0x20B2AA),
+    ("lightskyblue", 0x87CEFA),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xB0C4DE),
+    ("lightyellow", 0xFFFFE0),
+    ("lime", 0x00FF00),
+    ("limegreen", 0x32CD32),
+    ("linen", 0xFAF0E6),
+    ("magenta", 0xFF00FF),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66CDAA),
+    ("mediumblue", 0x0000CD),
+    ("mediumorchid", 0xBA55D3),
+    ("mediumpurple", 0x9370DB),
+    ("mediumseagreen", 0x3CB371),
+    ("mediumslateblue", 0x7B68EE),
+    ("mediumspringgreen", 0x00FA9A),
+    ("mediumturquoise", 0x48D1CC),
+    ("mediumvioletred", 0xC71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xF5FFFA),
+    ("mistyrose", 0xFFE4E1),
+    ("moccasin", 0xFFE4B5),
+    ("navajowhite", 0xFFDEAD),
+    ("navy", 0x000080),
+    ("oldlace", 0xFDF5E6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6B8E23),
+    ("orange", 0xFFA500),
+    ("orangered", 0xFF4500),
+    ("orchid", 0xDA70D6),
+    ("palegoldenrod", 0xEEE8AA),
+    ("palegreen", 0x98FB98),
+    ("paleturquoise", 0xAFEEEE),
+    ("palevioletred", 0xDB7093),
+    ("papayawhip", 0xFFEFD5),
+    ("peachpuff", 0xFFDAB9),
+    ("peru", 0xCD853F),
+    ("pink", 0xFFC0CB),
+    ("plum", 0xDDA0DD),
+    ("powderblue", 0xB0E0E6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xFF0000),
+    ("rosybrown", 0xBC8F8F),
+    ("royalblue", 0x4169E1),
+    ("saddlebrown", 0x8B4513),
+    ("salmon", 0xFA8072),
+    ("sandybrown", 0xF4A460),
+    ("seagreen", 0x2E8B57),
+    ("seashell", 0xFFF5EE),
+    ("sienna", 0xA0522D),
+    ("silver", 0xC0C0C0),
+    ("skyblue", 0x87CEEB),
+    ("slateblue", 0x6A5ACD),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xFFFAFA),
+    ("springgreen", 0x00FF7F),
+    ("steelblue", 0x4682B4),
+    ("tan", 0xD2B48C),
+    ("teal", 0x008080),
+    ("thistle", 0xD8BFD8),
+    ("tomato", 0xFF6347),
+    ("turquoise", 0x40E0D0),
+    ("violet", 0xEE82EE),
+    ("wheat", 0xF5DEB3),
+    ("white", 0xFFFFFF),
+    ("whitesmoke", 0xF5F5F5),
+    ("yellow", 0xFFFF00),
+    ("yellowgreen", 0x9ACD32),
+];
 
 thread_local! {
-    static PICKER_OVERLAY: RefCell<Option<PickerOverlay>> = RefCell::new(None);
-    static PICKER_SHIELD: RefCell<Option<PickerShieldWindow>> = RefCell::new(None);
+    static PICKER_OVERLAY: RefCell<Option<PickerOverlay>> = const { RefCell::new(None) };
+    static PICKER_SHIELD: RefCell<Option<PickerShieldWindow>> = const { RefCell::new(None) };
+    static WATCHER_WINDOW: RefCell<Option<WatcherWindow>> = const { RefCell::new(None) };
+    static IMAGE_PICKER: RefCell<Option<ImagePickerWindow>> = const { RefCell::new(None) };
+    static TRAY_HANDLE: RefCell<Option<ksni::blocking::Handle<AppTray>>> = const { RefCell::new(None) };
+    static UNDO_STACK: RefCell<UndoStack> = RefCell::new(UndoStack::default());
+    /// Full-precision current color; the UI's `current-color` only holds its 8-bit rounding.
+    static CURRENT_COLOR: Cell<Srgb> = const { Cell::new(Srgb::new(0.0, 0.0, 0.0)) };
+    /// Last `color-scheme` the Settings portal reported; true when it prefers dark.
+    static SYSTEM_DARK: RefCell<Option<bool>> = const { RefCell::new(None) };
+    /// Last `contrast` the Settings portal reported; true for high contrast.
+    static SYSTEM_HIGH_CONTRAST: RefCell<Option<bool>> = const { RefCell::new(None) };
+}
+
+/// Everything undo and redo restore.
+#[derive(Debug, Clone, PartialEq)]
+struct UndoState {
+    current: Srgb,
+    history: Vec<(u8, u8, u8)>,
+    palette: Vec<(u8, u8, u8)>,
+}
+
+#[derive(Default)]
+struct UndoStack {
+    /// State as of the last recorded step; the next change is measured against it.
+    baseline: Option<UndoState>,
+    undo: Vec<UndoState>,
+    redo: Vec<UndoState>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum PickerSource {
     Hotkey,
     Button,
+    Tray,
+}
+
+/// What happens with the result of a pick, independent of how it was triggered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PickOutcome {
+    /// Follow the Auto Copy setting.
+    Settings,
+    Copy(ColorField),
+    OpenDetails,
+    /// Two clicks span a region whose dominant colors go into history.
+    Palette,
+    /// The clicked point is sampled continuously in the watcher window.
+    Watch,
+    /// A dragged line is sampled into a gradient strip.
+    Gradient,
+    /// The picked color becomes the compare reference instead of a history entry.
+    Reference,
+}
+
+impl PickOutcome {
+    fn copy_field(self, ui: &AppWindow) -> Option<ColorField> {
+        match self {
+            Self::Settings => ui
+                .get_setting_autocopy()
+                .then(|| configured_copy_format(ui)),
+            Self::Copy(field) => Some(field),
+            Self::OpenDetails | Self::Palette | Self::Watch | Self::Gradient | Self::Reference => {
+                None
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
 struct PickerContext {
     source: PickerSource,
     was_visible_before_trigger: bool,
+    outcome: PickOutcome,
+    color_space: ReportSpace,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -66,12 +383,24 @@ enum SessionType {
     Unknown,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 enum ColorField {
     Hex,
     Rgb,
     Hsl,
     Hsv,
+    #[serde(rename = "display-p3")]
+    DisplayP3,
+    Rec2020,
+    /// Linear-light sRGB floats, as shaders expect.
+    #[serde(rename = "linear-float")]
+    LinearFloat,
+    /// sRGB-encoded floats in 0-1.
+    Float,
+    Rgb16,
+    /// Plain-language wording such as "dark desaturated blue, L 32%, near dark slate blue".
+    Description,
 }
 
 impl ColorField {
@@ -81,9 +410,112 @@ impl ColorField {
             "RGB" => Some(Self::Rgb),
             "HSL" => Some(Self::Hsl),
             "HSV" => Some(Self::Hsv),
+            "P3" => Some(Self::DisplayP3),
+            "2020" => Some(Self::Rec2020),
+            "LIN" => Some(Self::LinearFloat),
+            "FLT" => Some(Self::Float),
+            "U16" => Some(Self::Rgb16),
+            "DESC" => Some(Self::Description),
+            _ => None,
+        }
+    }
+
+    fn ui_label(self) -> &'static str {
+        match self {
+            Self::Hex => "HEX",
+            Self::Rgb => "RGB",
+            Self::Hsl => "HSL",
+            Self::Hsv => "HSV",
+            Self::DisplayP3 => "P3",
+            Self::Rec2020 => "2020",
+            Self::LinearFloat => "LIN",
+            Self::Float => "FLT",
+            Self::Rgb16 => "U16",
+            Self::Description => "DESC",
+        }
+    }
+
+    /// The `color()` space whose bare channels this field's row accepts.
+    fn color_space(self) -> Option<CssColorSpace> {
+        match self {
+            Self::DisplayP3 => Some(CssColorSpace::DisplayP3),
+            Self::Rec2020 => Some(CssColorSpace::Rec2020),
+            _ => None,
+        }
+    }
+}
+
+/// What goes on the clipboard next to (or instead of) the plain text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum ClipboardStyle {
+    Text,
+    /// `text/html` swatch span, with the plain text as the alternative.
+    Html,
+    /// `image/png` swatch only, replacing the text: arboard offers one kind of content per
+    /// copy. Value row copy buttons still copy text.
+    Image,
+}
+
+impl ClipboardStyle {
+    fn from_ui_label(label: &str) -> Option<Self> {
+        match label {
+            "Text" => Some(Self::Text),
+            "Text + HTML" => Some(Self::Html),
+            "Image only" => Some(Self::Image),
+            _ => None,
+        }
+    }
+
+    fn ui_label(self) -> &'static str {
+        match self {
+            Self::Text => "Text",
+            Self::Html => "Text + HTML",
+            Self::Image => "Image only",
+        }
+    }
+}
+
+/// Color space the ICC conversion targets; screen captures arrive in display RGB and are
+/// always stored as sRGB. Only X11 captures are converted; Wayland compositors return their
+/// own values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum ReportSpace {
+    /// Raw framebuffer values, no color management.
+    Display,
+    Srgb,
+    DisplayP3,
+}
+
+impl ReportSpace {
+    fn from_ui_label(label: &str) -> Option<Self> {
+        match label {
+            "Display" => Some(Self::Display),
+            "sRGB" => Some(Self::Srgb),
+            "Display P3" => Some(Self::DisplayP3),
             _ => None,
         }
     }
+
+    fn ui_label(self) -> &'static str {
+        match self {
+            Self::Display => "Display",
+            Self::Srgb => "sRGB",
+            Self::DisplayP3 => "Display P3",
+        }
+    }
+}
+
+enum ClipboardRequest {
+    Copy {
+        text: String,
+        rgb: (u8, u8, u8),
+    },
+    /// Plain text regardless of the clipboard style, for non-color snippets.
+    Text(String),
+    Style(ClipboardStyle),
+    Shutdown(mpsc::Sender<()>),
 }
 
 struct X11PointerGrab {
@@ -234,8 +666,59 @@ fn tray_icon_pixmap() -> Vec<Icon> {
     .clone()
 }
 
+/// The app icon with a swatch of `rgb` in its bottom-right corner.
+fn tray_icon_with_swatch(rgb: (u8, u8, u8)) -> Vec<Icon> {
+    let (r, g, b) = rgb;
+    let mut icons = tray_icon_pixmap();
+    for icon in &mut icons {
+        let size = icon.width.min(icon.height);
+        let swatch = size / 2;
+        let border = (size / 32).max(1);
+        let (left, top) = (icon.width - swatch, icon.height - swatch);
+        for y in top..icon.height {
+            for x in left..icon.width {
+                let idx = ((y * icon.width + x) * 4) as usize;
+                let on_border = x < left + border
+                    || y < top + border
+                    || x >= icon.width - border
+                    || y >= icon.height - border;
+                let argb = if on_border {
+                    [255, 0x20, 0x20, 0x20]
+                } else {
+                    [255, r, g, b]
+                };
+                if let Some(pixel) = icon.data.get_mut(idx..idx + 4) {
+                    pixel.copy_from_slice(&argb);
+                }
+            }
+        }
+    }
+    icons
+}
+
+/// PNG swatch used as a menu item icon.
+fn swatch_png(rgb: (u8, u8, u8)) -> Vec<u8> {
+    let (r, g, b) = rgb;
+    let swatch = RgbaImage::from_pixel(16, 16, Rgba([r, g, b, 255]));
+    let mut bytes = vec![];
+    if let Err(err) =
+        DynamicImage::ImageRgba8(swatch).write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+    {
+        eprintln!("tray: swatch encode failed: {err:?}");
+        return vec![];
+    }
+    bytes
+}
+
 struct AppTray {
     ui: slint::Weak<AppWindow>,
+    history_store: HistoryStore,
+    recent: Vec<(u8, u8, u8)>,
+    current: Srgb,
+    swatch_icon: bool,
+    autocopy: bool,
+    dark_mode: bool,
+    copy_format: ColorField,
 }
 
 impl Tray for AppTray {
@@ -248,11 +731,32 @@ impl Tray for AppTray {
     }
 
     fn icon_name(&self) -> String {
-        "archtoys".into()
+        // Hosts prefer a themed icon name over the pixmap, so drop it while showing the swatch.
+        if self.swatch_icon {
+            String::new()
+        } else {
+            "archtoys".into()
+        }
     }
 
     fn icon_pixmap(&self) -> Vec<Icon> {
-        tray_icon_pixmap()
+        if self.swatch_icon {
+            tray_icon_with_swatch(srgb_to_rgb8(self.current))
+        } else {
+            tray_icon_pixmap()
+        }
+    }
+
+    fn tool_tip(&self) -> ToolTip {
+        ToolTip {
+            title: "Archtoys Color Picker".into(),
+            description: format!(
+                "{} {}",
+                format_color(ColorField::Hex, self.current),
+                format_color(ColorField::Rgb, self.current)
+            ),
+            ..Default::default()
+        }
     }
 
     fn activate(&mut self, _x: i32, _y: i32) {
@@ -263,9 +767,25 @@ impl Tray for AppTray {
     }
 
     fn menu(&self) -> Vec<MenuItem<Self>> {
+        let recent_items: Vec<MenuItem<Self>> = self
+            .recent
+            .iter()
+            .map(|&rgb| {
+                StandardItem {
+                    label: format_canonical(ColorField::Hex, rgb),
+                    icon_data: swatch_png(rgb),
+                    activate: Box::new(move |this: &mut AppTray| {
+                        copy_color_async(format_canonical(this.copy_format, rgb), rgb);
+                    }),
+                    ..Default::default()
+                }
+                .into()
+            })
+            .collect();
+
         vec![
             StandardItem {
-                label: "Open".into(),
+                label: tr("Open"),
                 activate: Box::new(|this: &mut AppTray| {
                     let ui = this.ui.clone();
                     let _ = ui.upgrade_in_event_loop(|ui: AppWindow| {
@@ -276,7 +796,129 @@ impl Tray for AppTray {
             }
             .into(),
             StandardItem {
-                label: "Quit".into(),
+                label: tr("Pick Color"),
+                activate: Box::new(|this: &mut AppTray| {
+                    let history_store = this.history_store.clone();
+                    let _ = this.ui.upgrade_in_event_loop(move |ui: AppWindow| {
+                        begin_pick(
+                            &ui,
+                            &history_store,
+                            PickerSource::Tray,
+                            PickOutcome::Settings,
+                        );
+                    });
+                }),
+                ..Default::default()
+            }
+            .into(),
+            StandardItem {
+                label: tr("Open Image…"),
+                activate: Box::new(|this: &mut AppTray| {
+                    let history_store = this.history_store.clone();
+                    let _ = this.ui.upgrade_in_event_loop(move |ui: AppWindow| {
+                        choose_image_file(&ui, &history_store);
+                    });
+                }),
+                ..Default::default()
+            }
+            .into(),
+            StandardItem {
+                label: tr("Pick Gradient"),
+                activate: Box::new(|this: &mut AppTray| {
+                    let history_store = this.history_store.clone();
+                    let _ = this.ui.upgrade_in_event_loop(move |ui: AppWindow| {
+                        begin_pick(
+                            &ui,
+                            &history_store,
+                            PickerSource::Tray,
+                            PickOutcome::Gradient,
+                        );
+                    });
+                }),
+                ..Default::default()
+            }
+            .into(),
+            StandardItem {
+                label: tr("Watch Point"),
+                activate: Box::new(|this: &mut AppTray| {
+                    let history_store = this.history_store.clone();
+                    let _ = this.ui.upgrade_in_event_loop(move |ui: AppWindow| {
+                        begin_pick(&ui, &history_store, PickerSource::Tray, PickOutcome::Watch);
+                    });
+                }),
+                ..Default::default()
+            }
+            .into(),
+            SubMenu {
+                label: tr("Recent Colors"),
+                enabled: !recent_items.is_empty(),
+                submenu: recent_items,
+                ..Default::default()
+            }
+            .into(),
+            MenuItem::Separator,
+            SubMenu {
+                label: tr("Copy Format"),
+                submenu: vec![RadioGroup {
+                    selected: COPY_FORMATS
+                        .iter()
+                        .position(|field| *field == self.copy_format)
+                        .unwrap_or(0),
+                    select: Box::new(|this: &mut AppTray, index| {
+                        let Some(field) = COPY_FORMATS.get(index).copied() else {
+                            return;
+                        };
+                        this.copy_format = field;
+                        let _ = this.ui.upgrade_in_event_loop(move |ui: AppWindow| {
+                            ui.set_setting_copy_format(field.ui_label().into());
+                            ui.invoke_settings_changed();
+                        });
+                    }),
+                    options: COPY_FORMATS
+                        .iter()
+                        .map(|field| RadioItem {
+                            label: field.ui_label().into(),
+                            ..Default::default()
+                        })
+                        .collect(),
+                }
+                .into()],
+                ..Default::default()
+            }
+            .into(),
+            CheckmarkItem {
+                label: tr("Auto Copy"),
+                checked: self.autocopy,
+                activate: Box::new(|this: &mut AppTray| {
+                    this.autocopy = !this.autocopy;
+                    let autocopy = this.autocopy;
+                    let _ = this.ui.upgrade_in_event_loop(move |ui: AppWindow| {
+                        ui.set_setting_autocopy(autocopy);
+                        ui.invoke_settings_changed();
+                    });
+                }),
+                ..Default::default()
+            }
+            .into(),
+            CheckmarkItem {
+                label: tr("Dark Mode"),
+                checked: self.dark_mode,
+                activate: Box::new(|this: &mut AppTray| {
+                    this.dark_mode = !this.dark_mode;
+                    let dark_mode = this.dark_mode;
+                    let _ = this.ui.upgrade_in_event_loop(move |ui: AppWindow| {
+                        // Choosing a mode by hand stops following the desktop.
+                        ui.set_setting_follow_system(false);
+                        ui.global::<Skin>().set_dark_mode(dark_mode);
+                        ui.invoke_settings_changed();
+                    });
+                }),
+                ..Default::default()
+            }
+            .into(),
+            MenuItem::Separator,
+            StandardItem {
+                label: tr("Quit"),
                 activate: Box::new(|_this: &mut AppTray| {
                     let _ = slint::invoke_from_event_loop(|| {
                         slint::quit_event_loop().ok();
@@ -289,30 +931,175 @@ impl Tray for AppTray {
     }
 }
 
+/// Pushes history and settings into the tray menu.
+fn sync_tray(ui: &AppWindow, history_store: &HistoryStore) {
+    let recent: Vec<(u8, u8, u8)> = {
+        let guard = history_store.lock().unwrap();
+        guard.iter().take(TRAY_RECENT_COLORS).copied().collect()
+    };
+    let swatch_icon = ui.get_setting_tray_swatch();
+    let autocopy = ui.get_setting_autocopy();
+    let dark_mode = ui.global::<Skin>().get_dark_mode();
+    let copy_format = configured_copy_format(ui);
+
+    update_tray(move |tray| {
+        tray.recent = recent;
+        tray.swatch_icon = swatch_icon;
+        tray.autocopy = autocopy;
+        tray.dark_mode = dark_mode;
+        tray.copy_format = copy_format;
+    });
+}
+
+fn update_tray(f: impl FnOnce(&mut AppTray) + Send) {
+    TRAY_HANDLE.with(|slot| {
+        if let Some(handle) = slot.borrow().as_ref() {
+            handle.update(f);
+        }
+    });
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 struct AppConfig {
     dark_mode: bool,
+    high_contrast: bool,
+    /// Take dark mode from the desktop's `color-scheme` instead of `dark_mode`.
+    setting_follow_system: bool,
     setting_minimize: bool,
     setting_autocopy: bool,
     setting_autostart: bool,
+    setting_tray_swatch: bool,
+    setting_notify: bool,
     setting_hotkey: String,
+    setting_copy_format: ColorField,
+    setting_clipboard_style: ClipboardStyle,
+    setting_color_space: ReportSpace,
+    /// Largest ΔE2000 that compare mode still reports as a match.
+    #[serde(deserialize_with = "deserialize_tolerance")]
+    setting_compare_tolerance: f32,
+    hotkey_bindings: Vec<HotkeyBinding>,
     history: Vec<[u8; 3]>,
+    palette: Vec<[u8; 3]>,
+    watch_interval_ms: u64,
+    /// Largest per-channel change that is still "the same color"; 0 disables the trigger.
+    watch_threshold: u8,
+    /// Shell command run when the watched color changes beyond the threshold.
+    watch_command: String,
+    /// Picker overlay size in logical pixels; 0 fits the contents.
+    overlay_width: u32,
+    overlay_height: u32,
+    /// Logical distance from the cursor, mirrored when the overlay would leave the monitor.
+    overlay_offset_x: i32,
+    overlay_offset_y: i32,
+    overlay_formats: Vec<ColorField>,
+    overlay_name: bool,
+    overlay_loupe: bool,
+    /// Side of the magnified square, in screen pixels; rounded up to an odd number.
+    overlay_loupe_pixels: u32,
+    overlay_coordinates: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum HotkeyAction {
+    Pick,
+    PickCopyHex,
+    PickCopy(ColorField),
+    PickOpenDetails,
+    ToggleWindow,
+    CopyLast,
+    ExtractPalette,
+    PasteColor,
+    WatchPoint,
+    OpenImage,
+    PickGradient,
+}
+
+impl HotkeyAction {
+    fn description(self) -> String {
+        match self {
+            Self::Pick => tr("Pick a color"),
+            Self::PickCopyHex => tr("Pick and copy HEX"),
+            Self::PickCopy(field) => tr_format("Pick and copy {}", &[&field.ui_label()]),
+            Self::PickOpenDetails => tr("Pick and open details"),
+            Self::ToggleWindow => tr("Show or hide the window"),
+            Self::CopyLast => tr("Copy the last color again"),
+            Self::ExtractPalette => tr("Extract a palette from a region"),
+            Self::PasteColor => tr("Detect a color from the clipboard"),
+            Self::WatchPoint => tr("Watch a screen point over time"),
+            Self::OpenImage => tr("Pick from an image file"),
+            Self::PickGradient => tr("Sample a gradient along a dragged line"),
+        }
+    }
+}
+
+/// Extra global hotkey on top of `setting_hotkey`, which always starts a plain pick.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HotkeyBinding {
+    hotkey: String,
+    action: HotkeyAction,
+}
+
+/// Something the Ctrl+K command palette can run.
+#[derive(Debug, Clone, Copy)]
+enum AppCommand {
+    Action(HotkeyAction),
+    Copy(ColorField),
+    Undo,
+    Redo,
+    ClearHistory,
+    PaletteColor(usize),
+    NamedColor(u32),
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
             dark_mode: false,
+            high_contrast: false,
+            setting_follow_system: false,
             setting_minimize: false,
             setting_autocopy: false,
             setting_autostart: false,
+            setting_tray_swatch: false,
+            setting_notify: true,
             setting_hotkey: DEFAULT_HOTKEY_TEXT.to_string(),
+            setting_copy_format: ColorField::Hex,
+            setting_clipboard_style: ClipboardStyle::Text,
+            setting_color_space: ReportSpace::Srgb,
+            setting_compare_tolerance: COMPARE_DEFAULT_TOLERANCE,
+            hotkey_bindings: vec![],
             history: vec![],
+            palette: vec![],
+            watch_interval_ms: WATCH_DEFAULT_INTERVAL_MS,
+            watch_threshold: 0,
+            watch_command: String::new(),
+            overlay_width: 0,
+            overlay_height: 0,
+            overlay_offset_x: OVERLAY_DEFAULT_OFFSET,
+            overlay_offset_y: OVERLAY_DEFAULT_OFFSET,
+            overlay_formats: vec![ColorField::Hex],
+            overlay_name: false,
+            overlay_loupe: false,
+            overlay_loupe_pixels: OVERLAY_DEFAULT_LOUPE_PIXELS,
+            overlay_coordinates: false,
         }
     }
 }
 
+/// Falls back to the default for `null`, which serde_json writes for infinities, and for
+/// negative values, instead of rejecting the whole config.
+fn deserialize_tolerance<'de, D>(deserializer: D) -> Result<f32, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let tolerance = Option::<f32>::deserialize(deserializer)?;
+    Ok(tolerance
+        .filter(|tolerance| tolerance.is_finite() && *tolerance >= 0.0)
+        .unwrap_or(COMPARE_DEFAULT_TOLERANCE))
+}
+
 fn config_base_dir() -> PathBuf {
     if let Ok(dir) = std::env::var("XDG_CONFIG_HOME") {
         return PathBuf::from(dir);
@@ -329,6 +1116,122 @@ fn config_path() -> PathBuf {
         .join("config.json")
 }
 
+fn theme_path() -> PathBuf {
+    config_base_dir()
+        .join("archtoys-color-picker")
+        .join(THEME_FILE_NAME)
+}
+
+/// User colors from `theme.json`. Values take any notation `detect_color` accepts; keys
+/// under `"dark"` replace the top-level ones while dark mode is on.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+struct ThemeFile {
+    bg_color: Option<String>,
+    content_bg: Option<String>,
+    text_main: Option<String>,
+    text_muted: Option<String>,
+    border: Option<String>,
+    icon_tint: Option<String>,
+    accent: Option<String>,
+    dark: Option<Box<ThemeFile>>,
+}
+
+impl ThemeFile {
+    /// Resolves one mode; unset keys stay transparent so `Skin` keeps its built-in color.
+    fn colors(&self, dark: bool) -> ThemeColors {
+        let overrides = self.dark.as_deref().filter(|_| dark);
+        let pick = |key: fn(&ThemeFile) -> &Option<String>| {
+            let value = overrides
+                .and_then(|theme| key(theme).as_deref())
+                .or(key(self).as_deref());
+            value
+                .and_then(detect_color)
+                .map(|(r, g, b)| Color::from_rgb_u8(r, g, b))
+                .unwrap_or_default()
+        };
+        ThemeColors {
+            bg_color: pick(|theme| &theme.bg_color),
+            content_bg: pick(|theme| &theme.content_bg),
+            text_main: pick(|theme| &theme.text_main),
+            text_muted: pick(|theme| &theme.text_muted),
+            border: pick(|theme| &theme.border),
+            icon_tint: pick(|theme| &theme.icon_tint),
+            accent: pick(|theme| &theme.accent),
+        }
+    }
+
+    fn unparsable_values(&self) -> Vec<&str> {
+        let mut values: Vec<&str> = [
+            &self.bg_color,
+            &self.content_bg,
+            &self.text_main,
+            &self.text_muted,
+            &self.border,
+            &self.icon_tint,
+            &self.accent,
+        ]
+        .into_iter()
+        .filter_map(|value| value.as_deref())
+        .filter(|value| detect_color(value).is_none())
+        .collect();
+        if let Some(dark) = &self.dark {
+            values.extend(dark.unparsable_values());
+        }
+        values
+    }
+}
+
+/// Loads `theme.json` into both `Skin` variants. A missing file restores the built-in
+/// palette; a broken one keeps the current theme.
+fn apply_user_theme(ui: &AppWindow) {
+    let theme = match fs::read_to_string(theme_path()) {
+        Ok(data) => match serde_json::from_str::<ThemeFile>(&data) {
+            Ok(theme) => theme,
+            Err(err) => {
+                eprintln!("theme: parse failed: {err}");
+                return;
+            }
+        },
+        Err(err) if err.kind() == ErrorKind::NotFound => ThemeFile::default(),
+        Err(err) => {
+            eprintln!("theme: read failed: {err}");
+            return;
+        }
+    };
+    for value in theme.unparsable_values() {
+        eprintln!("theme: ignoring unrecognized color {value:?}");
+    }
+
+    let skin = ui.global::<Skin>();
+    skin.set_light_theme(theme.colors(false));
+    skin.set_dark_theme(theme.colors(true));
+}
+
+/// Gives another window the main window's modes, theme and accent.
+fn copy_skin(ui: &AppWindow, target: &Skin<'_>) {
+    let skin = ui.global::<Skin>();
+    target.set_dark_mode(skin.get_dark_mode());
+    target.set_high_contrast(skin.get_high_contrast());
+    target.set_light_theme(skin.get_light_theme());
+    target.set_dark_theme(skin.get_dark_theme());
+    target.set_system_accent(skin.get_system_accent());
+}
+
+/// Applies the desktop's color scheme and contrast while Follow System Theme is on and
+/// the portal has reported them.
+fn apply_followed_color_scheme(ui: &AppWindow) {
+    if !ui.get_setting_follow_system() {
+        return;
+    }
+    if let Some(dark) = SYSTEM_DARK.with(|slot| *slot.borrow()) {
+        ui.global::<Skin>().set_dark_mode(dark);
+    }
+    if let Some(high) = SYSTEM_HIGH_CONTRAST.with(|slot| *slot.borrow()) {
+        ui.global::<Skin>().set_high_contrast(high);
+    }
+}
+
 fn autostart_path() -> PathBuf {
     config_base_dir().join("autostart").join("archtoys.desktop")
 }
@@ -359,7 +1262,29 @@ fn sync_autostart_entry(enabled: bool) {
 fn load_config() -> Option<AppConfig> {
     let path = config_path();
     let data = fs::read_to_string(path).ok()?;
-    serde_json::from_str(&data).ok()
+    let cfg = serde_json::from_str(&data).ok()?;
+    *CONFIG_SYNCED_TEXT.lock().unwrap() = Some(data);
+    Some(cfg)
+}
+
+/// Last config read from or written to disk; carries fields the UI does not mirror.
+fn synced_config() -> AppConfig {
+    CONFIG_SYNCED_TEXT
+        .lock()
+        .unwrap()
+        .as_deref()
+        .and_then(|text| serde_json::from_str(text).ok())
+        .unwrap_or_default()
+}
+
+/// Returns the on-disk config if it parses and differs from what we last read or wrote.
+fn read_external_config() -> Option<(String, AppConfig)> {
+    let data = fs::read_to_string(config_path()).ok()?;
+    if CONFIG_SYNCED_TEXT.lock().unwrap().as_deref() == Some(data.as_str()) {
+        return None;
+    }
+    let cfg = serde_json::from_str(&data).ok()?;
+    Some((data, cfg))
 }
 
 fn save_config(cfg: &AppConfig) {
@@ -372,15 +1297,18 @@ fn save_config(cfg: &AppConfig) {
     }
     match serde_json::to_string_pretty(cfg) {
         Ok(data) => {
-            if let Err(err) = fs::write(path, data) {
+            if let Err(err) = fs::write(path, &data) {
                 eprintln!("config: write failed: {err:?}");
+                return;
             }
+            *CONFIG_SYNCED_TEXT.lock().unwrap() = Some(data);
+            PENDING_PICKS.lock().unwrap().clear();
         }
         Err(err) => eprintln!("config: serialize failed: {err:?}"),
     }
 }
 
-fn snapshot_config(ui: &AppWindow, history_store: &Arc<Mutex<Vec<(u8, u8, u8)>>>) -> AppConfig {
+fn snapshot_config(ui: &AppWindow, history_store: &HistoryStore) -> AppConfig {
     let skin = ui.global::<Skin>();
     let history = {
         let guard = history_store.lock().unwrap();
@@ -388,21 +1316,46 @@ fn snapshot_config(ui: &AppWindow, history_store: &Arc<Mutex<Vec<(u8, u8, u8)>>>
     };
     AppConfig {
         dark_mode: skin.get_dark_mode(),
+        high_contrast: skin.get_high_contrast(),
+        setting_follow_system: ui.get_setting_follow_system(),
         setting_minimize: ui.get_setting_minimize(),
         setting_autocopy: ui.get_setting_autocopy(),
         setting_autostart: ui.get_setting_autostart(),
+        setting_tray_swatch: ui.get_setting_tray_swatch(),
+        setting_notify: ui.get_setting_notify(),
         setting_hotkey: ui.get_setting_hotkey().to_string(),
+        setting_copy_format: configured_copy_format(ui),
+        setting_clipboard_style: configured_clipboard_style(ui),
+        setting_color_space: configured_color_space(ui),
+        setting_compare_tolerance: ui.get_compare_tolerance(),
         history,
+        palette: ui
+            .get_palette_model()
+            .iter()
+            .map(|color| [color.red(), color.green(), color.blue()])
+            .collect(),
+        ..synced_config()
     }
 }
 
-fn apply_config(ui: &AppWindow, history_store: &Arc<Mutex<Vec<(u8, u8, u8)>>>, cfg: &AppConfig) {
+fn apply_config(ui: &AppWindow, history_store: &HistoryStore, cfg: &AppConfig) {
     let skin = ui.global::<Skin>();
     skin.set_dark_mode(cfg.dark_mode);
+    skin.set_high_contrast(cfg.high_contrast);
+    ui.set_setting_follow_system(cfg.setting_follow_system);
+    apply_followed_color_scheme(ui);
     ui.set_setting_minimize(cfg.setting_minimize);
     ui.set_setting_autocopy(cfg.setting_autocopy);
     ui.set_setting_autostart(cfg.setting_autostart);
+    ui.set_setting_tray_swatch(cfg.setting_tray_swatch);
+    ui.set_setting_notify(cfg.setting_notify);
+    set_palette_model(ui, cfg.palette.iter().map(|rgb| (rgb[0], rgb[1], rgb[2])));
     ui.set_setting_hotkey(cfg.setting_hotkey.clone().into());
+    ui.set_setting_copy_format(cfg.setting_copy_format.ui_label().into());
+    ui.set_setting_clipboard_style(cfg.setting_clipboard_style.ui_label().into());
+    send_clipboard_request(ClipboardRequest::Style(cfg.setting_clipboard_style));
+    ui.set_setting_color_space(cfg.setting_color_space.ui_label().into());
+    ui.set_compare_tolerance(cfg.setting_compare_tolerance);
 
     if !cfg.history.is_empty() {
         let mut guard = history_store.lock().unwrap();
@@ -413,66 +1366,274 @@ fn apply_config(ui: &AppWindow, history_store: &Arc<Mutex<Vec<(u8, u8, u8)>>>, c
     }
 }
 
-fn persist_config(ui: &AppWindow, history_store: &Arc<Mutex<Vec<(u8, u8, u8)>>>) {
+fn persist_config(ui: &AppWindow, history_store: &HistoryStore) {
+    record_undo_step(ui, history_store);
+    if CONFIG_WATCHER_ACTIVE.load(Ordering::SeqCst) && read_external_config().is_some() {
+        // An external edit is pending; the watcher merges this change into it and saves.
+        return;
+    }
     let cfg = snapshot_config(ui, history_store);
     save_config(&cfg);
 }
 
-fn apply_native_window_constraints(ui: &AppWindow) {
-    use slint::winit_030::{winit, WinitWindowAccessor};
+fn current_undo_state(ui: &AppWindow, history_store: &HistoryStore) -> UndoState {
+    UndoState {
+        current: current_color_wide(),
+        history: history_store.lock().unwrap().clone(),
+        palette: ui
+            .get_palette_model()
+            .iter()
+            .map(|color| (color.red(), color.green(), color.blue()))
+            .collect(),
+    }
+}
 
-    ui.window().with_winit_window(|window| {
-        window.set_min_inner_size(Some(winit::dpi::LogicalSize::new(
-            WINDOW_MIN_WIDTH,
-            WINDOW_MIN_HEIGHT,
-        )));
-        window.set_max_inner_size(Some(winit::dpi::LogicalSize::new(
-            WINDOW_MAX_WIDTH,
-            WINDOW_MAX_HEIGHT,
-        )));
-        window.set_resizable(true);
+/// Turns whatever changed since the last step into an undo step. Every change that is
+/// persisted passes through here; selecting a color without saving calls it directly.
+fn record_undo_step(ui: &AppWindow, history_store: &HistoryStore) {
+    let state = current_undo_state(ui, history_store);
+    UNDO_STACK.with(|stack| {
+        let mut stack = stack.borrow_mut();
+        match stack.baseline.replace(state.clone()) {
+            Some(previous) if previous != state => {
+                stack.undo.push(previous);
+                if stack.undo.len() > UNDO_LIMIT {
+                    stack.undo.remove(0);
+                }
+                stack.redo.clear();
+            }
+            _ => {}
+        }
     });
 }
 
-fn apply_hidden_startup_state(ui: &AppWindow) {
-    use slint::winit_030::WinitWindowAccessor;
-
-    // Keep startup fully tray-only: no visible top-level window/task entry.
-    ui.window().hide().ok();
-    ui.window().with_winit_window(|window| {
-        window.set_visible(false);
+/// Restores the state before the last step, or with `redo` the one after it.
+fn step_undo(ui: &AppWindow, history_store: &HistoryStore, redo: bool) {
+    let restored = UNDO_STACK.with(|stack| {
+        let mut stack = stack.borrow_mut();
+        let stack = &mut *stack;
+        let (from, to) = if redo {
+            (&mut stack.redo, &mut stack.undo)
+        } else {
+            (&mut stack.undo, &mut stack.redo)
+        };
+        let state = from.pop()?;
+        if let Some(baseline) = stack.baseline.replace(state.clone()) {
+            to.push(baseline);
+        }
+        Some(state)
     });
+    let Some(state) = restored else {
+        return;
+    };
+
+    *history_store.lock().unwrap() = state.history;
+    sync_history_model(ui, history_store);
+    set_palette_model(ui, state.palette.into_iter());
+    update_ui_colors_precise(ui, state.current);
+    // Matches the new baseline, so saving records no step of its own.
+    persist_config(ui, history_store);
 }
 
-fn normalize_hotkey_text(input: &str) -> String {
-    let tokens: Vec<String> = input
-        .split('+')
-        .map(|token| token.trim())
-        .filter(|token| !token.is_empty())
-        .map(|token| match token.to_ascii_uppercase().as_str() {
-            "META" | "WIN" | "WINDOWS" => "Super".to_string(),
-            "CTL" => "Ctrl".to_string(),
-            _ => token.to_string(),
-        })
+/// Merges a config file edited by another tool with the local state.
+///
+/// Merge rule: a top-level key changed locally since the last sync keeps the local value,
+/// every other key takes the file's. History is the file's, with the colors picked since
+/// the last save (and not removed since) on top. Returns the merged config and whether it
+/// differs from the file.
+fn merge_external_config(local: &AppConfig, file_text: &str) -> Option<(AppConfig, bool)> {
+    let mut merged: serde_json::Value = serde_json::from_str(file_text).ok()?;
+    // Compared as parsed text, so floats match the way they were written.
+    let local_value: serde_json::Value =
+        serde_json::from_str(&serde_json::to_string(local).ok()?).ok()?;
+    let base: Option<serde_json::Value> = CONFIG_SYNCED_TEXT
+        .lock()
+        .unwrap()
+        .as_deref()
+        .and_then(|text| serde_json::from_str(text).ok());
+
+    let mut local_wins = false;
+    if let (Some(base), Some(merged), Some(local)) =
+        (base, merged.as_object_mut(), local_value.as_object())
+    {
+        for (key, value) in local {
+            if key != "history" && base.get(key) != Some(value) && merged.get(key) != Some(value) {
+                merged.insert(key.clone(), value.clone());
+                local_wins = true;
+            }
+        }
+    }
+    let mut cfg: AppConfig = serde_json::from_value(merged).ok()?;
+
+    let pending: Vec<[u8; 3]> = PENDING_PICKS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|rgb| local.history.contains(&[rgb.0, rgb.1, rgb.2]))
+        .map(|(r, g, b)| [*r, *g, *b])
         .collect();
-    tokens.join("+")
+    local_wins |= !pending.is_empty();
+    cfg.history.splice(0..0, pending);
+    Some((cfg, local_wins))
 }
 
-fn parse_hotkey_text(input: &str) -> Result<(HotKey, String), String> {
-    let normalized = normalize_hotkey_text(input);
-    if normalized.is_empty() {
-        return Err("hotkey cannot be empty".to_string());
-    }
+/// Applies a config file edited by another tool, merged as `merge_external_config`
+/// describes; a merge that kept local changes is saved back.
+fn reload_external_config(ui: &AppWindow, history_store: &HistoryStore, hotkeys: &HotkeyState) {
+    let Some((data, _)) = read_external_config() else {
+        return;
+    };
+    let local = snapshot_config(ui, history_store);
+    let Some((cfg, kept_local)) = merge_external_config(&local, &data) else {
+        return;
+    };
 
-    let parsed = std::panic::catch_unwind(|| HotKey::from_str(&normalized))
-        .map_err(|_| format!("invalid hotkey `{normalized}`"))?
-        .map_err(|err| format!("invalid hotkey `{normalized}`: {err}"))?;
-    Ok((parsed, normalized))
-}
+    *CONFIG_SYNCED_TEXT.lock().unwrap() = Some(data);
+    PENDING_PICKS.lock().unwrap().clear();
 
-fn normalize_captured_hotkey_key(raw: &str) -> Option<String> {
-    let trimmed = raw.trim();
-    if trimmed.is_empty() {
+    let file_hotkey = cfg.setting_hotkey.clone();
+    apply_config(ui, history_store, &cfg);
+    sync_history_model(ui, history_store);
+    sync_autostart_entry(ui.get_setting_autostart());
+
+    register_hotkey_bindings(hotkeys, &cfg.hotkey_bindings);
+    let active_text = hotkeys.active_text.lock().unwrap().clone();
+    if normalize_hotkey_text(&file_hotkey) != active_text {
+        apply_hotkey_candidate(ui, history_store, hotkeys, &file_hotkey);
+    } else {
+        ui.set_setting_hotkey(active_text.into());
+        rebind_portal_shortcuts(ui, hotkeys);
+    }
+
+    if kept_local {
+        persist_config(ui, history_store);
+    } else {
+        record_undo_step(ui, history_store);
+    }
+}
+
+fn spawn_config_watcher(
+    ui_weak: slint::Weak<AppWindow>,
+    history_store: HistoryStore,
+    hotkeys: HotkeyState,
+) {
+    let path = config_path();
+    let (Some(dir), Some(file_name)) = (path.parent(), path.file_name()) else {
+        return;
+    };
+    let file_name = file_name.to_os_string();
+
+    // theme.json sits next to config.json and is reloaded by the same watch.
+    // Watch the directory, not the file: dotfile managers usually replace it by rename.
+    if let Err(err) = fs::create_dir_all(dir) {
+        eprintln!("config watcher: create dir failed: {err:?}");
+        return;
+    }
+    let mut inotify = match Inotify::init() {
+        Ok(inotify) => inotify,
+        Err(err) => {
+            eprintln!("config watcher: inotify init failed: {err:?}");
+            return;
+        }
+    };
+    if let Err(err) = inotify.watches().add(
+        dir,
+        WatchMask::CLOSE_WRITE | WatchMask::MOVED_TO | WatchMask::CREATE,
+    ) {
+        eprintln!("config watcher: watch failed: {err:?}");
+        return;
+    }
+
+    CONFIG_WATCHER_ACTIVE.store(true, Ordering::SeqCst);
+    thread::spawn(move || {
+        let mut buffer = [0u8; 4096];
+        loop {
+            let events = match inotify.read_events_blocking(&mut buffer) {
+                Ok(events) => events,
+                Err(err) => {
+                    eprintln!("config watcher: read failed: {err:?}");
+                    CONFIG_WATCHER_ACTIVE.store(false, Ordering::SeqCst);
+                    return;
+                }
+            };
+
+            let mut touched = false;
+            let mut theme_touched = false;
+            for event in events {
+                touched |= event.name == Some(file_name.as_os_str());
+                theme_touched |= event.name == Some(OsStr::new(THEME_FILE_NAME));
+            }
+            if theme_touched {
+                let _ = ui_weak.upgrade_in_event_loop(|ui| apply_user_theme(&ui));
+            }
+            if !touched || read_external_config().is_none() {
+                continue;
+            }
+
+            let history_store2 = history_store.clone();
+            let hotkeys2 = hotkeys.clone();
+            let _ = ui_weak.upgrade_in_event_loop(move |ui| {
+                reload_external_config(&ui, &history_store2, &hotkeys2);
+            });
+        }
+    });
+}
+
+fn apply_native_window_constraints(ui: &AppWindow) {
+    use slint::winit_030::{winit, WinitWindowAccessor};
+
+    ui.window().with_winit_window(|window| {
+        window.set_min_inner_size(Some(winit::dpi::LogicalSize::new(
+            WINDOW_MIN_WIDTH,
+            WINDOW_MIN_HEIGHT,
+        )));
+        window.set_max_inner_size(Some(winit::dpi::LogicalSize::new(
+            WINDOW_MAX_WIDTH,
+            WINDOW_MAX_HEIGHT,
+        )));
+        window.set_resizable(true);
+    });
+}
+
+fn apply_hidden_startup_state(ui: &AppWindow) {
+    use slint::winit_030::WinitWindowAccessor;
+
+    // Keep startup fully tray-only: no visible top-level window/task entry.
+    ui.window().hide().ok();
+    ui.window().with_winit_window(|window| {
+        window.set_visible(false);
+    });
+}
+
+fn normalize_hotkey_text(input: &str) -> String {
+    let tokens: Vec<String> = input
+        .split('+')
+        .map(|token| token.trim())
+        .filter(|token| !token.is_empty())
+        .map(|token| match token.to_ascii_uppercase().as_str() {
+            "META" | "WIN" | "WINDOWS" => "Super".to_string(),
+            "CTL" => "Ctrl".to_string(),
+            _ => token.to_string(),
+        })
+        .collect();
+    tokens.join("+")
+}
+
+fn parse_hotkey_text(input: &str) -> Result<(HotKey, String), String> {
+    let normalized = normalize_hotkey_text(input);
+    if normalized.is_empty() {
+        return Err("hotkey cannot be empty".to_string());
+    }
+
+    let parsed = std::panic::catch_unwind(|| HotKey::from_str(&normalized))
+        .map_err(|_| format!("invalid hotkey `{normalized}`"))?
+        .map_err(|err| format!("invalid hotkey `{normalized}`: {err}"))?;
+    Ok((parsed, normalized))
+}
+
+fn normalize_captured_hotkey_key(raw: &str) -> Option<String> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
         return None;
     }
 
@@ -575,6 +1736,229 @@ fn build_hotkey_from_capture(
     Ok(parts.join("+"))
 }
 
+#[derive(Clone)]
+struct HotkeyState {
+    manager: Option<Arc<GlobalHotKeyManager>>,
+    active: Arc<Mutex<HotKey>>,
+    active_id: Arc<Mutex<u32>>,
+    active_text: Arc<Mutex<String>>,
+    bindings: Arc<Mutex<Vec<RegisteredBinding>>>,
+    portal: Option<PortalShortcuts>,
+}
+
+struct RegisteredBinding {
+    hotkey: HotKey,
+    text: String,
+    action: HotkeyAction,
+}
+
+/// Registers the keybinding table, replacing whatever it registered before.
+fn register_hotkey_bindings(hotkeys: &HotkeyState, bindings: &[HotkeyBinding]) {
+    let mut registered = hotkeys.bindings.lock().unwrap();
+    if let Some(manager) = hotkeys.manager.as_ref() {
+        for binding in registered.iter() {
+            let _ = manager.unregister(binding.hotkey);
+        }
+    }
+    registered.clear();
+
+    let primary_id = *hotkeys.active_id.lock().unwrap();
+    for binding in bindings {
+        let (hotkey, text) = match parse_hotkey_text(&binding.hotkey) {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                eprintln!("hotkey: binding skipped: {err}");
+                continue;
+            }
+        };
+        if hotkey.id() == primary_id
+            || registered
+                .iter()
+                .any(|other| other.hotkey.id() == hotkey.id())
+        {
+            eprintln!(
+                "hotkey: `{text}` is already bound; skipping {:?}",
+                binding.action
+            );
+            continue;
+        }
+        if let Some(manager) = hotkeys.manager.as_ref() {
+            if let Err(err) = manager.register(hotkey) {
+                eprintln!("hotkey: failed to register binding `{text}`: {err}");
+                continue;
+            }
+        }
+        registered.push(RegisteredBinding {
+            hotkey,
+            text,
+            action: binding.action,
+        });
+    }
+}
+
+fn hotkey_action_for_id(hotkeys: &HotkeyState, id: u32) -> Option<HotkeyAction> {
+    if id == *hotkeys.active_id.lock().unwrap() {
+        return Some(HotkeyAction::Pick);
+    }
+    hotkeys
+        .bindings
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|binding| binding.hotkey.id() == id)
+        .map(|binding| binding.action)
+}
+
+fn portal_action_for_id(hotkeys: &HotkeyState, id: &str) -> Option<HotkeyAction> {
+    if id == PORTAL_PICK_SHORTCUT_ID {
+        return Some(HotkeyAction::Pick);
+    }
+    let index: usize = id.strip_prefix("binding-")?.parse().ok()?;
+    hotkeys
+        .bindings
+        .lock()
+        .unwrap()
+        .get(index)
+        .map(|binding| binding.action)
+}
+
+/// Converts `Ctrl+Super+C` into the XDG shortcut trigger syntax (`CTRL+LOGO+c`).
+fn portal_trigger_from_hotkey_text(text: &str) -> String {
+    normalize_hotkey_text(text)
+        .split('+')
+        .map(|token| match token.to_ascii_uppercase().as_str() {
+            "CTRL" | "CONTROL" => "CTRL".to_string(),
+            "ALT" => "ALT".to_string(),
+            "SHIFT" => "SHIFT".to_string(),
+            "SUPER" => "LOGO".to_string(),
+            _ if token.chars().count() == 1 => token.to_ascii_lowercase(),
+            _ => token.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+/// Sends the current hotkeys to the GlobalShortcuts portal and shows the trigger the
+/// compositor actually assigned to the pick shortcut.
+fn rebind_portal_shortcuts(ui: &AppWindow, hotkeys: &HotkeyState) {
+    let Some(portal) = hotkeys.portal.clone() else {
+        return;
+    };
+
+    let mut shortcuts = vec![(
+        PORTAL_PICK_SHORTCUT_ID.to_string(),
+        HotkeyAction::Pick.description(),
+        portal_trigger_from_hotkey_text(&hotkeys.active_text.lock().unwrap()),
+    )];
+    for (index, binding) in hotkeys.bindings.lock().unwrap().iter().enumerate() {
+        shortcuts.push((
+            format!("binding-{index}"),
+            binding.action.description(),
+            portal_trigger_from_hotkey_text(&binding.text),
+        ));
+    }
+
+    let ui_weak = ui.as_weak();
+    thread::spawn(move || {
+        let assigned = match portal.bind(&shortcuts) {
+            Ok(triggers) => triggers
+                .get(PORTAL_PICK_SHORTCUT_ID)
+                .cloned()
+                .unwrap_or_default(),
+            Err(err) => {
+                eprintln!("hotkey: {err}");
+                return;
+            }
+        };
+        let _ = ui_weak.upgrade_in_event_loop(move |ui| {
+            ui.set_setting_hotkey_assigned(assigned.into());
+        });
+    });
+}
+
+fn run_hotkey_action(ui: &AppWindow, history_store: &HistoryStore, action: HotkeyAction) {
+    let outcome = match action {
+        HotkeyAction::Pick => PickOutcome::Settings,
+        HotkeyAction::PickCopyHex => PickOutcome::Copy(ColorField::Hex),
+        HotkeyAction::PickCopy(field) => PickOutcome::Copy(field),
+        HotkeyAction::PickOpenDetails => PickOutcome::OpenDetails,
+        HotkeyAction::ExtractPalette => PickOutcome::Palette,
+        HotkeyAction::WatchPoint => PickOutcome::Watch,
+        HotkeyAction::PickGradient => PickOutcome::Gradient,
+        HotkeyAction::ToggleWindow => {
+            if ui.window().is_visible() {
+                ui.window().hide().ok();
+            } else {
+                ui.window().show().ok();
+            }
+            return;
+        }
+        HotkeyAction::PasteColor => {
+            paste_color_from_clipboard(ui, history_store);
+            return;
+        }
+        HotkeyAction::OpenImage => {
+            choose_image_file(ui, history_store);
+            return;
+        }
+        HotkeyAction::CopyLast => {
+            let last = history_store.lock().unwrap().first().copied();
+            if let Some(rgb) = last {
+                copy_color_async(format_canonical(ColorField::Hex, rgb), rgb);
+            }
+            return;
+        }
+    };
+
+    begin_pick(ui, history_store, PickerSource::Hotkey, outcome);
+}
+
+/// Swaps the registered global hotkey for `candidate`, restoring the previous one on failure.
+fn apply_hotkey_candidate(
+    ui: &AppWindow,
+    history_store: &HistoryStore,
+    hotkeys: &HotkeyState,
+    candidate: &str,
+) {
+    let (new_hotkey, normalized_text) = match parse_hotkey_text(candidate) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            eprintln!("hotkey: {err}");
+            let current_text = hotkeys.active_text.lock().unwrap().clone();
+            ui.set_setting_hotkey(current_text.into());
+            return;
+        }
+    };
+
+    let old_hotkey = *hotkeys.active.lock().unwrap();
+    if new_hotkey.id() == old_hotkey.id() {
+        ui.set_setting_hotkey(normalized_text.clone().into());
+        *hotkeys.active_text.lock().unwrap() = normalized_text;
+        persist_config(ui, history_store);
+        return;
+    }
+
+    if let Some(manager) = hotkeys.manager.as_ref() {
+        let _ = manager.unregister(old_hotkey);
+        if let Err(err) = manager.register(new_hotkey) {
+            eprintln!("hotkey: failed to register `{}`: {err}", normalized_text);
+            let _ = manager.register(old_hotkey);
+            let previous_text = hotkeys.active_text.lock().unwrap().clone();
+            ui.set_setting_hotkey(previous_text.into());
+            return;
+        }
+    } else if hotkeys.portal.is_none() {
+        eprintln!("hotkey: global manager unavailable on this session; saving only");
+    }
+
+    *hotkeys.active.lock().unwrap() = new_hotkey;
+    *hotkeys.active_id.lock().unwrap() = new_hotkey.id();
+    *hotkeys.active_text.lock().unwrap() = normalized_text.clone();
+    ui.set_setting_hotkey(normalized_text.into());
+    rebind_portal_shortcuts(ui, hotkeys);
+    persist_config(ui, history_store);
+}
+
 fn detect_session_type() -> SessionType {
     match std::env::var("XDG_SESSION_TYPE")
         .unwrap_or_default()
@@ -595,276 +1979,2826 @@ fn format_rgb(r: u8, g: u8, b: u8) -> String {
     format!("rgb({r},{g},{b})")
 }
 
-fn format_hsl(r: u8, g: u8, b: u8) -> String {
-    let srgb = Srgb::new(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
-    let hsl: Hsl = Hsl::from_color(srgb);
+fn format_hsl(color: Srgb) -> String {
+    let hsl: Hsl = Hsl::from_color(color);
     let h = hsl.hue.into_degrees().round().rem_euclid(360.0);
     let s = (hsl.saturation * 100.0).round().clamp(0.0, 100.0);
     let l = (hsl.lightness * 100.0).round().clamp(0.0, 100.0);
     format!("hsl({h:.0},{s:.0}%,{l:.0}%)")
 }
 
-fn format_hsv(r: u8, g: u8, b: u8) -> String {
-    let srgb = Srgb::new(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
-    let hsv: Hsv = Hsv::from_color(srgb);
+fn format_hsv(color: Srgb) -> String {
+    let hsv: Hsv = Hsv::from_color(color);
     let h = hsv.hue.into_degrees().round().rem_euclid(360.0);
     let s = (hsv.saturation * 100.0).round().clamp(0.0, 100.0);
     let v = (hsv.value * 100.0).round().clamp(0.0, 100.0);
     format!("hsv({h:.0},{s:.0}%,{v:.0}%)")
 }
 
+/// Float channels with four decimals, e.g. `0.6038, 0.4621, 0.4125`.
+fn format_float_channels(channels: [f32; 3]) -> String {
+    let [c1, c2, c3] = channels.map(|channel| (channel * 10_000.0).round() / 10_000.0 + 0.0);
+    format!("{c1:.4}, {c2:.4}, {c3:.4}")
+}
+
 fn format_canonical(field: ColorField, rgb: (u8, u8, u8)) -> String {
-    let (r, g, b) = rgb;
+    format_color(field, srgb_from_rgb8(rgb))
+}
+
+/// Formats `color` without first rounding it to 8 bits, so typed values round-trip. Only the
+/// wide-gamut rows see channels outside sRGB; the others get the gamut-mapped color.
+fn format_color(field: ColorField, wide: Srgb) -> String {
+    let color = fit_srgb(wide);
+    let (r, g, b) = srgb_to_rgb8(color);
     match field {
         ColorField::Hex => format_hex(r, g, b),
         ColorField::Rgb => format_rgb(r, g, b),
-        ColorField::Hsl => format_hsl(r, g, b),
-        ColorField::Hsv => format_hsv(r, g, b),
+        ColorField::Hsl => format_hsl(color),
+        ColorField::Hsv => format_hsv(color),
+        ColorField::DisplayP3 => format_color_function(CssColorSpace::DisplayP3, wide),
+        ColorField::Rec2020 => format_color_function(CssColorSpace::Rec2020, wide),
+        ColorField::LinearFloat => {
+            let linear = color.into_linear();
+            format_float_channels([linear.red, linear.green, linear.blue])
+        }
+        ColorField::Float => format_float_channels([color.red, color.green, color.blue]),
+        ColorField::Rgb16 => {
+            let [r, g, b] =
+                [color.red, color.green, color.blue].map(|channel| (channel * 65535.0).round());
+            format!("{r:.0}, {g:.0}, {b:.0}")
+        }
+        ColorField::Description => describe_color(color),
     }
 }
 
-fn calculate_shades(r: u8, g: u8, b: u8) -> (Color, Color, Color, Color) {
-    let lighter_2 = Color::from_rgb_u8(
-        ((r as f32 * 1.5).min(255.0)) as u8,
-        ((g as f32 * 1.5).min(255.0)) as u8,
-        ((b as f32 * 1.5).min(255.0)) as u8,
-    );
-    let lighter_1 = Color::from_rgb_u8(
-        ((r as f32 * 1.2).min(255.0)) as u8,
-        ((g as f32 * 1.2).min(255.0)) as u8,
-        ((b as f32 * 1.2).min(255.0)) as u8,
-    );
-    let darker_1 = Color::from_rgb_u8(
-        (r as f32 * 0.7) as u8,
-        (g as f32 * 0.7) as u8,
-        (b as f32 * 0.7) as u8,
-    );
-    let darker_2 = Color::from_rgb_u8(
-        (r as f32 * 0.5) as u8,
-        (g as f32 * 0.5) as u8,
-        (b as f32 * 0.5) as u8,
-    );
-    (lighter_2, lighter_1, darker_1, darker_2)
+/// Color family named by the DESC row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HueFamily {
+    Black,
+    White,
+    Gray,
+    Pink,
+    Red,
+    Orange,
+    Brown,
+    Yellow,
+    Olive,
+    Green,
+    Cyan,
+    Blue,
+    Purple,
+    Magenta,
+}
+
+impl HueFamily {
+    fn label(self) -> String {
+        match self {
+            Self::Black => tr("black"),
+            Self::White => tr("white"),
+            Self::Gray => tr("gray"),
+            Self::Pink => tr("pink"),
+            Self::Red => tr("red"),
+            Self::Orange => tr("orange"),
+            Self::Brown => tr("brown"),
+            Self::Yellow => tr("yellow"),
+            Self::Olive => tr("olive"),
+            Self::Green => tr("green"),
+            Self::Cyan => tr("cyan"),
+            Self::Blue => tr("blue"),
+            Self::Purple => tr("purple"),
+            Self::Magenta => tr("magenta"),
+        }
+    }
+}
+
+/// OKLCH hue families, each covering the angles below its bound; the rest is pink again.
+const HUE_FAMILIES: [(f32, HueFamily); 9] = [
+    (15.0, HueFamily::Pink),
+    (45.0, HueFamily::Red),
+    (80.0, HueFamily::Orange),
+    (120.0, HueFamily::Yellow),
+    (165.0, HueFamily::Green),
+    (215.0, HueFamily::Cyan),
+    (280.0, HueFamily::Blue),
+    (310.0, HueFamily::Purple),
+    (340.0, HueFamily::Magenta),
+];
+
+/// Wording for color-blind users. OKLCH gives the lightness word, the L percentage and the
+/// hue family, HSL the saturation word, and the nearest CSS name anchors the rest.
+fn describe_color(color: Srgb) -> String {
+    let hsl: Hsl = Hsl::from_color(color);
+    let oklch: Oklch = color.into_color();
+    let lightness = oklch.l;
+    let achromatic = oklch.chroma < 0.02;
+
+    let family = if achromatic {
+        match lightness {
+            l if l < 0.2 => HueFamily::Black,
+            l if l > 0.97 => HueFamily::White,
+            _ => HueFamily::Gray,
+        }
+    } else {
+        let hue = oklch.hue.into_positive_degrees();
+        let family = HUE_FAMILIES
+            .iter()
+            .find(|(bound, _)| hue < *bound)
+            .map_or(HueFamily::Pink, |(_, family)| *family);
+        // Dark oranges and yellows read as their own colors.
+        match family {
+            HueFamily::Orange if lightness < 0.55 => HueFamily::Brown,
+            HueFamily::Yellow if lightness < 0.7 => HueFamily::Olive,
+            family => family,
+        }
+    };
+    let lightness_word = match lightness {
+        l if l < 0.27 => Some(tr("very dark")),
+        l if l < 0.46 => Some(tr("dark")),
+        l if l < 0.72 => None,
+        l if l < 0.88 => Some(tr("light")),
+        _ => Some(tr("very light")),
+    };
+    let saturation_word = match hsl.saturation {
+        _ if achromatic => None,
+        s if s < 0.15 => Some(tr("grayish")),
+        s if s < 0.4 => Some(tr("desaturated")),
+        s if s < 0.7 => Some(tr("moderate")),
+        s if s < 0.9 => Some(tr("strong")),
+        _ => Some(tr("vivid")),
+    };
+
+    let words = match family {
+        HueFamily::Black | HueFamily::White => family.label(),
+        _ => [lightness_word, saturation_word, Some(family.label())]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" "),
+    };
+    tr_format(
+        "{}, L {}%, near {}",
+        &[
+            &words,
+            &format!("{:.0}", lightness * 100.0),
+            &spoken_color_name(nearest_color_name(srgb_to_rgb8(color))),
+        ],
+    )
+}
+
+/// CSS `color(<space> c1 c2 c3)` with four decimals per channel.
+fn format_color_function(space: CssColorSpace, color: Srgb) -> String {
+    let [c1, c2, c3] = space
+        .encode_srgb(color)
+        .map(|channel| (channel * 10_000.0).round() / 10_000.0 + 0.0);
+    format!("color({} {c1:.4} {c2:.4} {c3:.4})", space.css_name())
+}
+
+fn calculate_shades(color: Srgb) -> (Color, Color, Color, Color) {
+    let shade = |factor: f32| {
+        let (r, g, b) = srgb_to_rgb8(Srgb::new(
+            color.red * factor,
+            color.green * factor,
+            color.blue * factor,
+        ));
+        Color::from_rgb_u8(r, g, b)
+    };
+    (shade(1.5), shade(1.2), shade(0.7), shade(0.5))
+}
+
+/// The current color at full precision, inside sRGB; everything that reads or copies it
+/// starts here.
+fn current_color() -> Srgb {
+    fit_srgb(current_color_wide())
+}
+
+/// The current color as picked, which a Display P3 pick may leave outside sRGB.
+fn current_color_wide() -> Srgb {
+    CURRENT_COLOR.with(Cell::get)
 }
 
-fn update_preview_color(ui: &AppWindow, r: u8, g: u8, b: u8) {
+fn update_preview_color(ui: &AppWindow, color: Srgb) {
+    CURRENT_COLOR.with(|current| current.set(color));
+    let color = fit_srgb(color);
+    let (r, g, b) = srgb_to_rgb8(color);
     ui.set_current_color(Color::from_rgb_u8(r, g, b));
-    let (lighter_2, lighter_1, darker_1, darker_2) = calculate_shades(r, g, b);
+    let (lighter_2, lighter_1, darker_1, darker_2) = calculate_shades(color);
     ui.set_shade_lighter_2(lighter_2);
     ui.set_shade_lighter_1(lighter_1);
     ui.set_shade_darker_1(darker_1);
     ui.set_shade_darker_2(darker_2);
+    update_mixer(ui);
+    update_compare(ui);
+}
+
+/// Interpolates `from` towards `to` by `t` (0-1) in `space`.
+fn mix_colors(a: Srgb, b: Srgb, t: f32, space: MixSpace) -> Srgb {
+    let lerp = |x: f32, y: f32| x + (y - x) * t;
+
+    let mixed: Srgb = match space {
+        MixSpace::Srgb => Srgb::new(
+            lerp(a.red, b.red),
+            lerp(a.green, b.green),
+            lerp(a.blue, b.blue),
+        ),
+        MixSpace::LinearRgb => {
+            let (a, b) = (a.into_linear::<f32>(), b.into_linear::<f32>());
+            Srgb::from_linear(LinSrgb::new(
+                lerp(a.red, b.red),
+                lerp(a.green, b.green),
+                lerp(a.blue, b.blue),
+            ))
+        }
+        MixSpace::Oklab => {
+            let (a, b): (Oklab, Oklab) = (a.into_color(), b.into_color());
+            Oklab::new(lerp(a.l, b.l), lerp(a.a, b.a), lerp(a.b, b.b)).into_color()
+        }
+        MixSpace::OklchShorter | MixSpace::OklchLonger => {
+            let (a, b): (Oklch, Oklch) = (a.into_color(), b.into_color());
+            // A gray has no meaningful hue, so it borrows the other color's.
+            let achromatic = |color: &Oklch| color.chroma < 1e-4;
+            let hue_a = if achromatic(&a) { b.hue } else { a.hue }.into_degrees();
+            let hue_b = if achromatic(&b) { a.hue } else { b.hue }.into_degrees();
+
+            let mut delta = (hue_b - hue_a).rem_euclid(360.0);
+            if (space == MixSpace::OklchShorter && delta > 180.0)
+                || (space == MixSpace::OklchLonger && delta < 180.0)
+            {
+                delta -= 360.0;
+            }
+            Oklch::new(lerp(a.l, b.l), lerp(a.chroma, b.chroma), hue_a + delta * t).into_color()
+        }
+    };
+    clamp_srgb(mixed)
+}
+
+/// Recomputes the mixer result and step strip from the current and second colors.
+fn update_mixer(ui: &AppWindow) {
+    let space = MixSpace::from_ui_label(&ui.get_mix_space()).unwrap_or(MixSpace::Oklab);
+    let second = ui.get_mix_color();
+    let from = current_color();
+    let to = srgb_from_rgb8((second.red(), second.green(), second.blue()));
+    let to_color = |color: Srgb| {
+        let (r, g, b) = srgb_to_rgb8(color);
+        Color::from_rgb_u8(r, g, b)
+    };
+
+    let mixed = mix_colors(from, to, ui.get_mix_ratio().clamp(0.0, 1.0), space);
+    ui.set_mix_result(to_color(mixed));
+
+    let steps: Vec<Color> = (0..MIX_STEPS)
+        .map(|index| {
+            let t = index as f32 / (MIX_STEPS - 1) as f32;
+            to_color(mix_colors(from, to, t, space))
+        })
+        .collect();
+    ui.set_mix_steps(ModelRc::from(Rc::new(VecModel::from(steps))));
 }
 
 fn update_ui_colors(ui: &AppWindow, r: u8, g: u8, b: u8) {
-    update_preview_color(ui, r, g, b);
-    let rgb = (r, g, b);
-    ui.set_val_hex(format_canonical(ColorField::Hex, rgb).into());
-    ui.set_val_rgb(format_canonical(ColorField::Rgb, rgb).into());
-    ui.set_val_hsl(format_canonical(ColorField::Hsl, rgb).into());
-    ui.set_val_hsv(format_canonical(ColorField::Hsv, rgb).into());
+    update_ui_colors_precise(ui, srgb_from_rgb8((r, g, b)));
 }
 
-fn update_ui_preview_except_field(ui: &AppWindow, editing_field: ColorField, r: u8, g: u8, b: u8) {
-    update_preview_color(ui, r, g, b);
-    let rgb = (r, g, b);
+/// Like `update_ui_colors`, but `color` becomes the current color as is; only the preview
+/// swatch and history see the 8-bit rounding.
+fn update_ui_colors_precise(ui: &AppWindow, color: Srgb) {
+    update_ui_preview_except_field(ui, None, color);
+    update_tray(move |tray| tray.current = color);
+    ui.set_color_space_note("".into());
+    ui.set_history_selected(-1);
+    sync_editor(ui, fit_srgb(color));
+}
 
-    if editing_field != ColorField::Hex {
-        ui.set_val_hex(format_canonical(ColorField::Hex, rgb).into());
-    }
-    if editing_field != ColorField::Rgb {
-        ui.set_val_rgb(format_canonical(ColorField::Rgb, rgb).into());
-    }
-    if editing_field != ColorField::Hsl {
-        ui.set_val_hsl(format_canonical(ColorField::Hsl, rgb).into());
-    }
-    if editing_field != ColorField::Hsv {
-        ui.set_val_hsv(format_canonical(ColorField::Hsv, rgb).into());
+fn value_row_text(ui: &AppWindow, field: ColorField) -> SharedString {
+    match field {
+        ColorField::Hex => ui.get_val_hex(),
+        ColorField::Rgb => ui.get_val_rgb(),
+        ColorField::Hsl => ui.get_val_hsl(),
+        ColorField::Hsv => ui.get_val_hsv(),
+        ColorField::DisplayP3 => ui.get_val_p3(),
+        ColorField::Rec2020 => ui.get_val_rec2020(),
+        ColorField::LinearFloat => ui.get_val_linear(),
+        ColorField::Float => ui.get_val_float(),
+        ColorField::Rgb16 => ui.get_val_rgb16(),
+        ColorField::Description => ui.get_val_description(),
     }
 }
 
-fn parse_hex_flexible(value: &str) -> Option<(u8, u8, u8)> {
-    let clean = value.trim().trim_start_matches('#');
-    if clean.len() != 6 {
-        return None;
+/// Updates the preview and every value row except `editing_field`, which the caller owns
+/// while the user is typing or dragging.
+fn update_ui_preview_except_field(ui: &AppWindow, editing_field: Option<ColorField>, color: Srgb) {
+    update_preview_color(ui, color);
+
+    type SetRow = fn(&AppWindow, SharedString);
+    let rows: [(ColorField, SetRow); 10] = [
+        (ColorField::Hex, AppWindow::set_val_hex),
+        (ColorField::Rgb, AppWindow::set_val_rgb),
+        (ColorField::Hsl, AppWindow::set_val_hsl),
+        (ColorField::Hsv, AppWindow::set_val_hsv),
+        (ColorField::DisplayP3, AppWindow::set_val_p3),
+        (ColorField::Rec2020, AppWindow::set_val_rec2020),
+        (ColorField::LinearFloat, AppWindow::set_val_linear),
+        (ColorField::Float, AppWindow::set_val_float),
+        (ColorField::Rgb16, AppWindow::set_val_rgb16),
+        (ColorField::Description, AppWindow::set_val_description),
+    ];
+    for (field, set_row) in rows {
+        if editing_field != Some(field) {
+            set_row(ui, format_color(field, color).into());
+        }
     }
-
-    let upper = clean.to_ascii_uppercase();
-    let r = u8::from_str_radix(&upper[0..2], 16).ok()?;
-    let g = u8::from_str_radix(&upper[2..4], 16).ok()?;
-    let b = u8::from_str_radix(&upper[4..6], 16).ok()?;
-    Some((r, g, b))
 }
 
-fn inner_function_payload<'a>(value: &'a str, func_name: &str) -> &'a str {
-    let trimmed = value.trim();
-    let lower = trimmed.to_ascii_lowercase();
-    let prefix = format!("{func_name}(");
+/// Plane the visual editor shows: saturation/value of one hue, or OKLCH chroma/lightness.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EditorMode {
+    Hsv,
+    Oklch,
+}
 
-    if lower.starts_with(&prefix) && trimmed.ends_with(')') && trimmed.len() >= prefix.len() + 1 {
-        let start = prefix.len();
-        let end = trimmed.len() - 1;
-        trimmed[start..end].trim()
-    } else {
-        trimmed
+impl EditorMode {
+    fn from_ui_label(label: &str) -> Self {
+        if label == "OKLCH" {
+            Self::Oklch
+        } else {
+            Self::Hsv
+        }
     }
 }
 
-fn parse_rgb_permissive(value: &str) -> Option<(u8, u8, u8)> {
-    let payload = inner_function_payload(value, "rgb");
-    let parts: Vec<&str> = payload.split(',').map(str::trim).collect();
-    if parts.len() != 3 {
-        return None;
-    }
+/// Color at plane position `x`,`y` (0-1, y up) for `hue`, and whether it is inside sRGB.
+fn editor_plane_color(mode: EditorMode, hue: f32, x: f32, y: f32) -> (Srgb, bool) {
+    // Unclamped so colors outside sRGB can be told apart from the boundary.
+    let rgb: Srgb = match mode {
+        EditorMode::Hsv => Hsv::new(hue, x, y).into_color_unclamped(),
+        EditorMode::Oklch => Oklch::new(y, x * OKLCH_MAX_CHROMA, hue).into_color_unclamped(),
+    };
+    let in_gamut = [rgb.red, rgb.green, rgb.blue]
+        .iter()
+        .all(|channel| (-0.001..=1.001).contains(channel));
+    (rgb, in_gamut)
+}
 
-    let parse_component = |s: &str| -> Option<u8> {
-        let raw = s.parse::<i32>().ok()?;
-        Some(raw.clamp(0, 255) as u8)
+/// Renders the editor plane. Out-of-gamut OKLCH colors are dimmed and the gamut boundary
+/// is traced in white.
+fn editor_plane_image(mode: EditorMode, hue: f32) -> slint::Image {
+    let (width, height) = (EDITOR_PLANE_WIDTH, EDITOR_PLANE_HEIGHT);
+    let position = |px: u32, py: u32| {
+        (
+            px as f32 / (width - 1) as f32,
+            1.0 - py as f32 / (height - 1) as f32,
+        )
+    };
+    let in_gamut = |px: u32, py: u32| {
+        let (x, y) = position(px, py);
+        editor_plane_color(mode, hue, x, y).1
     };
 
-    Some((
-        parse_component(parts[0])?,
-        parse_component(parts[1])?,
-        parse_component(parts[2])?,
-    ))
-}
+    let mut buffer = SharedPixelBuffer::<Rgba8Pixel>::new(width, height);
+    let pixels = buffer.make_mut_slice();
+    for py in 0..height {
+        for px in 0..width {
+            let (x, y) = position(px, py);
+            let (rgb, inside) = editor_plane_color(mode, hue, x, y);
+            let (r, g, b) = srgb_to_rgb8(rgb);
+            let boundary = inside
+                && ((px + 1 < width && !in_gamut(px + 1, py)) || (py > 0 && !in_gamut(px, py - 1)));
+
+            pixels[(py * width + px) as usize] = if boundary {
+                Rgba8Pixel::new(255, 255, 255, 255)
+            } else if inside {
+                Rgba8Pixel::new(r, g, b, 255)
+            } else {
+                Rgba8Pixel::new(r / 3, g / 3, b / 3, 255)
+            };
+        }
+    }
+    slint::Image::from_rgba8(buffer)
+}
+
+fn editor_color(ui: &AppWindow) -> Srgb {
+    let mode = EditorMode::from_ui_label(&ui.get_editor_mode());
+    let (rgb, _) = editor_plane_color(
+        mode,
+        ui.get_editor_hue(),
+        ui.get_editor_x(),
+        ui.get_editor_y(),
+    );
+    clamp_srgb(rgb)
+}
+
+fn refresh_editor_plane(ui: &AppWindow) {
+    if ui.get_editor_open() {
+        let mode = EditorMode::from_ui_label(&ui.get_editor_mode());
+        ui.set_editor_plane(editor_plane_image(mode, ui.get_editor_hue()));
+    }
+}
+
+/// Moves the plane marker and hue slider to `srgb`. Grays keep the previous hue.
+fn sync_editor_plane(ui: &AppWindow, srgb: Srgb) {
+    let (hue, x, y, chromatic) = match EditorMode::from_ui_label(&ui.get_editor_mode()) {
+        EditorMode::Hsv => {
+            let hsv: Hsv = srgb.into_color();
+            let hue = hsv.hue.into_degrees().rem_euclid(360.0);
+            (hue, hsv.saturation, hsv.value, hsv.saturation > 0.0)
+        }
+        EditorMode::Oklch => {
+            let oklch: Oklch = srgb.into_color();
+            let hue = oklch.hue.into_degrees().rem_euclid(360.0);
+            let x = (oklch.chroma / OKLCH_MAX_CHROMA).clamp(0.0, 1.0);
+            (hue, x, oklch.l.clamp(0.0, 1.0), oklch.chroma > 1e-4)
+        }
+    };
+
+    ui.set_editor_x(x);
+    ui.set_editor_y(y);
+    if chromatic && (hue - ui.get_editor_hue()).abs() > 0.01 {
+        ui.set_editor_hue(hue);
+        refresh_editor_plane(ui);
+    }
+}
+
+fn sync_editor_channels(ui: &AppWindow, srgb: Srgb) {
+    if ui.get_editor_channels() == "HSL" {
+        let hsl: Hsl = srgb.into_color();
+        if hsl.saturation > 0.0 {
+            ui.set_editor_c1(hsl.hue.into_degrees().rem_euclid(360.0));
+        }
+        ui.set_editor_c2(hsl.saturation * 100.0);
+        ui.set_editor_c3(hsl.lightness * 100.0);
+    } else {
+        ui.set_editor_c1(srgb.red * 255.0);
+        ui.set_editor_c2(srgb.green * 255.0);
+        ui.set_editor_c3(srgb.blue * 255.0);
+    }
+}
+
+fn sync_editor(ui: &AppWindow, srgb: Srgb) {
+    sync_editor_plane(ui, srgb);
+    sync_editor_channels(ui, srgb);
+}
+
+/// Shows a color produced by dragging the plane or hue slider. The HSV row is written from
+/// the editor's own values so its hue survives dragging through grays.
+fn preview_editor_plane(ui: &AppWindow) {
+    let color = editor_color(ui);
+    match EditorMode::from_ui_label(&ui.get_editor_mode()) {
+        EditorMode::Hsv => {
+            update_ui_preview_except_field(ui, Some(ColorField::Hsv), color);
+            ui.set_val_hsv(
+                format!(
+                    "hsv({:.0},{:.0}%,{:.0}%)",
+                    ui.get_editor_hue().round().rem_euclid(360.0),
+                    ui.get_editor_x() * 100.0,
+                    ui.get_editor_y() * 100.0
+                )
+                .into(),
+            );
+        }
+        EditorMode::Oklch => update_ui_preview_except_field(ui, None, color),
+    }
+    sync_editor_channels(ui, color);
+}
+
+/// Shows a color produced by the channel sliders, writing their row from the slider values.
+fn preview_editor_channels(ui: &AppWindow) {
+    let (c1, c2, c3) = (ui.get_editor_c1(), ui.get_editor_c2(), ui.get_editor_c3());
+    let color = if ui.get_editor_channels() == "HSL" {
+        let color: Srgb = Hsl::new(c1, c2 / 100.0, c3 / 100.0).into_color();
+        update_ui_preview_except_field(ui, Some(ColorField::Hsl), color);
+        ui.set_val_hsl(format!("hsl({c1:.0},{c2:.0}%,{c3:.0}%)").into());
+        color
+    } else {
+        let color = clamp_srgb(Srgb::new(c1 / 255.0, c2 / 255.0, c3 / 255.0));
+        update_ui_preview_except_field(ui, Some(ColorField::Rgb), color);
+        ui.set_val_rgb(format!("rgb({c1:.0},{c2:.0},{c3:.0})").into());
+        color
+    };
+    sync_editor_plane(ui, color);
+}
+
+/// Parses `#rgb`, `#rgba`, `#rrggbb` and `#rrggbbaa`, with or without the `#`; alpha is ignored.
+fn parse_hex_flexible(value: &str) -> Option<(u8, u8, u8)> {
+    let clean = value.trim().trim_start_matches('#');
+    if !clean.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    match clean.len() {
+        3 | 4 => {
+            let digit = |i: usize| {
+                u8::from_str_radix(&clean[i..i + 1], 16)
+                    .ok()
+                    .map(|d| d * 17)
+            };
+            Some((digit(0)?, digit(1)?, digit(2)?))
+        }
+        6 | 8 => {
+            let r = u8::from_str_radix(&clean[0..2], 16).ok()?;
+            let g = u8::from_str_radix(&clean[2..4], 16).ok()?;
+            let b = u8::from_str_radix(&clean[4..6], 16).ok()?;
+            Some((r, g, b))
+        }
+        _ => None,
+    }
+}
+
+/// A token between the parentheses of a CSS color function.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CssToken {
+    Number(f32),
+    Percentage(f32),
+    /// An angle with a unit, already converted to degrees.
+    Angle(f32),
+    /// The CSS Color 4 `none` keyword (a missing component, treated as zero).
+    None,
+    Comma,
+    Slash,
+}
+
+fn tokenize_css_arguments(payload: &str) -> Option<Vec<CssToken>> {
+    let chars: Vec<char> = payload.chars().collect();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+
+    while index < chars.len() {
+        let ch = chars[index];
+        if ch.is_whitespace() {
+            index += 1;
+            continue;
+        }
+        if ch == ',' || ch == '/' {
+            tokens.push(if ch == ',' {
+                CssToken::Comma
+            } else {
+                CssToken::Slash
+            });
+            index += 1;
+            continue;
+        }
+
+        let start = index;
+        if ch.is_ascii_alphabetic() {
+            while index < chars.len() && chars[index].is_ascii_alphabetic() {
+                index += 1;
+            }
+            let ident: String = chars[start..index].iter().collect();
+            if !ident.eq_ignore_ascii_case("none") {
+                return None;
+            }
+            tokens.push(CssToken::None);
+            continue;
+        }
+
+        if matches!(ch, '+' | '-') {
+            index += 1;
+        }
+        while index < chars.len() {
+            let current = chars[index];
+            let exponent = matches!(current, 'e' | 'E')
+                && chars
+                    .get(index + 1)
+                    .is_some_and(|next| next.is_ascii_digit() || matches!(next, '+' | '-'));
+            if current.is_ascii_digit() || current == '.' {
+                index += 1;
+            } else if exponent {
+                index += 2;
+            } else {
+                break;
+            }
+        }
+        let number: String = chars[start..index].iter().collect();
+        let value = number.parse::<f32>().ok()?;
+
+        let unit_start = index;
+        while index < chars.len() && (chars[index].is_ascii_alphabetic() || chars[index] == '%') {
+            index += 1;
+        }
+        let unit = chars[unit_start..index]
+            .iter()
+            .collect::<String>()
+            .to_ascii_lowercase();
+        tokens.push(match unit.as_str() {
+            "" => CssToken::Number(value),
+            "%" => CssToken::Percentage(value),
+            "deg" => CssToken::Angle(value),
+            "rad" => CssToken::Angle(value.to_degrees()),
+            "grad" => CssToken::Angle(value * 0.9),
+            "turn" => CssToken::Angle(value * 360.0),
+            _ => return None,
+        });
+    }
+
+    Some(tokens)
+}
+
+/// Picks the three channels out of either the legacy `a, b, c[, alpha]` form or the
+/// space-separated `a b c[ / alpha]` form. Alpha is validated but dropped.
+fn css_channel_arguments(tokens: &[CssToken]) -> Option<[CssToken; 3]> {
+    use CssToken::{Comma, Slash};
+
+    let (channels, alpha) = match *tokens {
+        [a, Comma, b, Comma, c] | [a, b, c] => ([a, b, c], None),
+        [a, Comma, b, Comma, c, Comma, alpha] | [a, b, c, Slash, alpha] => ([a, b, c], Some(alpha)),
+        _ => return None,
+    };
+
+    if channels.iter().any(|token| matches!(token, Comma | Slash)) {
+        return None;
+    }
+    if let Some(alpha) = alpha {
+        if !matches!(
+            alpha,
+            CssToken::Number(_) | CssToken::Percentage(_) | CssToken::None
+        ) {
+            return None;
+        }
+    }
+    Some(channels)
+}
+
+/// An sRGB channel as 0-1: numbers are 0-255, percentages 0-100%.
+fn css_rgb_channel(token: CssToken) -> Option<f32> {
+    match token {
+        CssToken::Number(value) => Some(value / 255.0),
+        CssToken::Percentage(value) => Some(value / 100.0),
+        CssToken::None => Some(0.0),
+        _ => None,
+    }
+}
+
+/// A hue in degrees: bare numbers are degrees, angles carry their own unit.
+fn css_hue(token: CssToken) -> Option<f32> {
+    match token {
+        CssToken::Number(value) | CssToken::Angle(value) => Some(value.rem_euclid(360.0)),
+        CssToken::None => Some(0.0),
+        _ => None,
+    }
+}
+
+/// A saturation/lightness/whiteness style channel as 0-1; bare numbers count as percent.
+fn css_fraction(token: CssToken) -> Option<f32> {
+    match token {
+        CssToken::Number(value) | CssToken::Percentage(value) => {
+            Some((value / 100.0).clamp(0.0, 1.0))
+        }
+        CssToken::None => Some(0.0),
+        _ => None,
+    }
+}
+
+/// Space in which the mixer interpolates between two colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MixSpace {
+    Srgb,
+    LinearRgb,
+    Oklab,
+    OklchShorter,
+    OklchLonger,
+}
+
+impl MixSpace {
+    fn from_ui_label(label: &str) -> Option<Self> {
+        match label {
+            "sRGB" => Some(Self::Srgb),
+            "Linear RGB" => Some(Self::LinearRgb),
+            "OKLab" => Some(Self::Oklab),
+            "OKLCH (shorter)" => Some(Self::OklchShorter),
+            "OKLCH (longer)" => Some(Self::OklchLonger),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CssColorFunction {
+    Rgb,
+    Hsl,
+    Hsv,
+    Hwb,
+}
+
+impl CssColorFunction {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "rgb" | "rgba" => Some(Self::Rgb),
+            "hsl" | "hsla" => Some(Self::Hsl),
+            "hsv" | "hsva" | "hsb" => Some(Self::Hsv),
+            "hwb" => Some(Self::Hwb),
+            _ => None,
+        }
+    }
+}
+
+/// RGB-like spaces of the CSS `color()` function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CssColorSpace {
+    Srgb,
+    SrgbLinear,
+    DisplayP3,
+    Rec2020,
+    A98Rgb,
+    XyzD65,
+}
+
+impl CssColorSpace {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "srgb" => Some(Self::Srgb),
+            "srgb-linear" => Some(Self::SrgbLinear),
+            "display-p3" => Some(Self::DisplayP3),
+            "rec2020" => Some(Self::Rec2020),
+            "a98-rgb" => Some(Self::A98Rgb),
+            "xyz" | "xyz-d65" => Some(Self::XyzD65),
+            _ => None,
+        }
+    }
+
+    fn css_name(self) -> &'static str {
+        match self {
+            Self::Srgb => "srgb",
+            Self::SrgbLinear => "srgb-linear",
+            Self::DisplayP3 => "display-p3",
+            Self::Rec2020 => "rec2020",
+            Self::A98Rgb => "a98-rgb",
+            Self::XyzD65 => "xyz-d65",
+        }
+    }
+
+    /// Transfer function: encoded channel to linear light, mirrored for negative values.
+    fn decode(self, channel: f32) -> f32 {
+        let (sign, value) = (channel.signum(), channel.abs());
+        match self {
+            Self::Srgb | Self::DisplayP3 if value <= 0.040_45 => channel / 12.92,
+            Self::Srgb | Self::DisplayP3 => sign * ((value + 0.055) / 1.055).powf(2.4),
+            Self::Rec2020 if value < REC2020_BETA * 4.5 => channel / 4.5,
+            Self::Rec2020 => {
+                sign * ((value + REC2020_ALPHA - 1.0) / REC2020_ALPHA).powf(1.0 / 0.45)
+            }
+            Self::A98Rgb => sign * value.powf(563.0 / 256.0),
+            Self::SrgbLinear | Self::XyzD65 => channel,
+        }
+    }
+
+    fn encode(self, channel: f32) -> f32 {
+        let (sign, value) = (channel.signum(), channel.abs());
+        match self {
+            Self::Srgb | Self::DisplayP3 if value <= 0.003_130_8 => channel * 12.92,
+            Self::Srgb | Self::DisplayP3 => sign * (1.055 * value.powf(1.0 / 2.4) - 0.055),
+            Self::Rec2020 if value <= REC2020_BETA => channel * 4.5,
+            Self::Rec2020 => sign * (REC2020_ALPHA * value.powf(0.45) - (REC2020_ALPHA - 1.0)),
+            Self::A98Rgb => sign * value.powf(256.0 / 563.0),
+            Self::SrgbLinear | Self::XyzD65 => channel,
+        }
+    }
+
+    fn to_xyz(self) -> &'static Mat3 {
+        match self {
+            Self::Srgb | Self::SrgbLinear => &LINEAR_SRGB_TO_XYZ,
+            Self::DisplayP3 => &DISPLAY_P3_TO_XYZ,
+            Self::Rec2020 => &REC2020_TO_XYZ,
+            Self::A98Rgb => &A98_RGB_TO_XYZ,
+            Self::XyzD65 => &IDENTITY_MAT3,
+        }
+    }
+
+    /// XYZ to this space's linear channels; the inverse of `to_xyz`.
+    fn xyz_matrix(self) -> &'static Mat3 {
+        match self {
+            Self::Srgb | Self::SrgbLinear => &XYZ_TO_LINEAR_SRGB,
+            Self::DisplayP3 => &XYZ_TO_DISPLAY_P3,
+            Self::Rec2020 => &XYZ_TO_REC2020,
+            Self::A98Rgb => &XYZ_TO_A98_RGB,
+            Self::XyzD65 => &IDENTITY_MAT3,
+        }
+    }
+
+    /// Encoded channels of this space as linear sRGB, unclamped.
+    fn to_linear_srgb(self, channels: [f32; 3]) -> LinSrgb {
+        let linear = channels.map(|channel| self.decode(channel));
+        let [r, g, b] = mat3_mul(&XYZ_TO_LINEAR_SRGB, mat3_mul(self.to_xyz(), linear));
+        LinSrgb::new(r, g, b)
+    }
+
+    /// `color` as encoded channels of this space, unclamped.
+    fn encode_srgb(self, color: Srgb) -> [f32; 3] {
+        let linear = color.into_linear();
+        let xyz = mat3_mul(&LINEAR_SRGB_TO_XYZ, [linear.red, linear.green, linear.blue]);
+        mat3_mul(self.xyz_matrix(), xyz).map(|channel| self.encode(channel))
+    }
+}
+
+fn mat3_mul(matrix: &Mat3, vector: [f32; 3]) -> [f32; 3] {
+    matrix.map(|row| row[0] * vector[0] + row[1] * vector[1] + row[2] * vector[2])
+}
+
+fn in_srgb_gamut(color: LinSrgb) -> bool {
+    [color.red, color.green, color.blue]
+        .iter()
+        .all(|channel| (-0.000_1..=1.000_1).contains(channel))
+}
+
+/// CSS Color 4 gamut mapping: lowers OKLCH chroma at constant lightness and hue until
+/// clipping to sRGB moves the color by less than a just noticeable difference.
+fn gamut_map_to_srgb(color: LinSrgb) -> Srgb {
+    const JND: f32 = 0.02;
+    const EPSILON: f32 = 0.000_1;
+
+    if in_srgb_gamut(color) {
+        return Srgb::from_linear(color);
+    }
+    let origin: Oklch = color.into_color_unclamped();
+    if origin.l >= 1.0 {
+        return Srgb::new(1.0, 1.0, 1.0);
+    }
+    if origin.l <= 0.0 {
+        return Srgb::new(0.0, 0.0, 0.0);
+    }
+
+    let clip = |color: Oklch| -> LinSrgb {
+        let linear: LinSrgb = color.into_color_unclamped();
+        LinSrgb::new(
+            linear.red.clamp(0.0, 1.0),
+            linear.green.clamp(0.0, 1.0),
+            linear.blue.clamp(0.0, 1.0),
+        )
+    };
+    let delta_eok = |color: Oklch, clipped: LinSrgb| {
+        let (a, b): (Oklab, Oklab) = (color.into_color_unclamped(), clipped.into_color_unclamped());
+        ((a.l - b.l).powi(2) + (a.a - b.a).powi(2) + (a.b - b.b).powi(2)).sqrt()
+    };
+
+    let mut current = origin;
+    if delta_eok(current, clip(current)) < JND {
+        return Srgb::from_linear(clip(current));
+    }
+    let (mut min, mut max) = (0.0, origin.chroma);
+    let mut min_in_gamut = true;
+    while max - min > EPSILON {
+        current.chroma = (min + max) / 2.0;
+        if min_in_gamut && in_srgb_gamut(current.into_color_unclamped()) {
+            min = current.chroma;
+            continue;
+        }
+        let error = delta_eok(current, clip(current));
+        if error < JND {
+            if JND - error < EPSILON {
+                break;
+            }
+            min_in_gamut = false;
+            min = current.chroma;
+        } else {
+            max = current.chroma;
+        }
+    }
+    Srgb::from_linear(clip(current))
+}
+
+/// A `color()` channel: numbers are taken as-is, percentages map 100% to 1.
+fn css_color_channel(token: CssToken) -> Option<f32> {
+    match token {
+        CssToken::Number(value) => Some(value),
+        CssToken::Percentage(value) => Some(value / 100.0),
+        CssToken::None => Some(0.0),
+        _ => None,
+    }
+}
+
+/// Parses CSS `color(<space> c1 c2 c3 [/ alpha])` into unclamped linear sRGB. Without the
+/// wrapper the channels are read in `implied`.
+fn parse_color_function(value: &str, implied: Option<CssColorSpace>) -> Option<LinSrgb> {
+    let trimmed = value.trim();
+    let (space, payload) = match trimmed.find('(') {
+        Some(open) => {
+            if !trimmed[..open].trim().eq_ignore_ascii_case("color") {
+                return None;
+            }
+            let inner = trimmed[open + 1..].strip_suffix(')')?.trim_start();
+            let (name, channels) = inner.split_once(char::is_whitespace)?;
+            (CssColorSpace::from_name(name)?, channels)
+        }
+        None => (implied?, trimmed),
+    };
+    let [first, second, third] = css_channel_arguments(&tokenize_css_arguments(payload)?)?;
+    let channels = [
+        css_color_channel(first)?,
+        css_color_channel(second)?,
+        css_color_channel(third)?,
+    ];
+    Some(space.to_linear_srgb(channels))
+}
+
+fn parse_color_function_rgb8(value: &str, implied: Option<CssColorSpace>) -> Option<(u8, u8, u8)> {
+    parse_color_function(value, implied).map(|color| srgb_to_rgb8(gamut_map_to_srgb(color)))
+}
+
+/// Feedback for a `color()` value that lies outside sRGB and was gamut-mapped to fit.
+fn srgb_gamut_note(value: &str, implied: Option<CssColorSpace>) -> Option<String> {
+    let color = parse_color_function(value, implied).filter(|color| !in_srgb_gamut(*color))?;
+    let (r, g, b) = srgb_to_rgb8(gamut_map_to_srgb(color));
+    Some(tr_format(
+        "Outside sRGB, shown as {}",
+        &[&format_hex(r, g, b)],
+    ))
+}
+
+fn srgb_from_rgb8((r, g, b): (u8, u8, u8)) -> Srgb {
+    Srgb::new(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0)
+}
+
+/// Encodes linear sRGB without clamping, mirroring the curve for negative channels.
+fn srgb_unclamped(color: LinSrgb) -> Srgb {
+    let [r, g, b] =
+        [color.red, color.green, color.blue].map(|channel| CssColorSpace::Srgb.encode(channel));
+    Srgb::new(r, g, b)
+}
+
+/// `color` inside sRGB: in-range values are kept, wider ones such as a Display P3 pick are
+/// gamut-mapped the CSS way.
+fn fit_srgb(color: Srgb) -> Srgb {
+    let channels = [color.red, color.green, color.blue];
+    if channels
+        .iter()
+        .all(|channel| (-0.000_1..=1.000_1).contains(channel))
+    {
+        return clamp_srgb(color);
+    }
+    gamut_map_to_srgb(CssColorSpace::Srgb.to_linear_srgb(channels))
+}
+
+fn clamp_srgb(color: Srgb) -> Srgb {
+    Srgb::new(
+        color.red.clamp(0.0, 1.0),
+        color.green.clamp(0.0, 1.0),
+        color.blue.clamp(0.0, 1.0),
+    )
+}
+
+fn srgb_to_rgb8(rgb: Srgb) -> (u8, u8, u8) {
+    (
+        (rgb.red.clamp(0.0, 1.0) * 255.0).round() as u8,
+        (rgb.green.clamp(0.0, 1.0) * 255.0).round() as u8,
+        (rgb.blue.clamp(0.0, 1.0) * 255.0).round() as u8,
+    )
+}
+
+/// Parses a CSS Color 4 functional notation. Without a `name(...)` wrapper the arguments
+/// are read as `implied`, so a value row can take bare channels.
+fn parse_css_function(value: &str, implied: Option<CssColorFunction>) -> Option<Srgb> {
+    let trimmed = value.trim();
+    let (function, payload) = match trimmed.find('(') {
+        Some(open) => (
+            CssColorFunction::from_name(&trimmed[..open])?,
+            trimmed[open + 1..].strip_suffix(')')?,
+        ),
+        None => (implied?, trimmed),
+    };
+    let [first, second, third] = css_channel_arguments(&tokenize_css_arguments(payload)?)?;
+
+    let rgb: Srgb = match function {
+        CssColorFunction::Rgb => Srgb::new(
+            css_rgb_channel(first)?,
+            css_rgb_channel(second)?,
+            css_rgb_channel(third)?,
+        ),
+        CssColorFunction::Hsl => {
+            Hsl::new(css_hue(first)?, css_fraction(second)?, css_fraction(third)?).into_color()
+        }
+        CssColorFunction::Hsv => {
+            Hsv::new(css_hue(first)?, css_fraction(second)?, css_fraction(third)?).into_color()
+        }
+        CssColorFunction::Hwb => {
+            let (hue, whiteness, blackness) =
+                (css_hue(first)?, css_fraction(second)?, css_fraction(third)?);
+            if whiteness + blackness >= 1.0 {
+                let gray = whiteness / (whiteness + blackness);
+                Srgb::new(gray, gray, gray)
+            } else {
+                let value = 1.0 - blackness;
+                Hsv::new(hue, 1.0 - whiteness / value, value).into_color()
+            }
+        }
+    };
+
+    Some(clamp_srgb(rgb))
+}
+
+/// Three bare numbers such as `0.6038, 0.4621, 0.4125`, optionally bracketed.
+fn parse_bare_channels(value: &str) -> Option<[f32; 3]> {
+    let inner = value
+        .trim()
+        .trim_start_matches(['(', '[', '{'])
+        .trim_end_matches([')', ']', '}']);
+    let channels = css_channel_arguments(&tokenize_css_arguments(inner)?)?;
+    let number = |token| match token {
+        CssToken::Number(value) => Some(value),
+        _ => None,
+    };
+    Some([
+        number(channels[0])?,
+        number(channels[1])?,
+        number(channels[2])?,
+    ])
+}
+
+/// Parses `value` as typed into `field`'s row, keeping full precision. Anything the row's
+/// own syntax does not cover goes through `detect_color`.
+fn parse_color(field: ColorField, value: &str) -> Option<Srgb> {
+    match field {
+        ColorField::Hex => parse_hex_flexible(value).map(srgb_from_rgb8),
+        ColorField::Rgb => parse_css_function(value, Some(CssColorFunction::Rgb)),
+        ColorField::Hsl => parse_css_function(value, Some(CssColorFunction::Hsl)),
+        ColorField::Hsv => parse_css_function(value, Some(CssColorFunction::Hsv)),
+        ColorField::DisplayP3 | ColorField::Rec2020 => {
+            parse_color_function(value, field.color_space()).map(gamut_map_to_srgb)
+        }
+        ColorField::LinearFloat => {
+            parse_bare_channels(value).map(|[r, g, b]| Srgb::from_linear(LinSrgb::new(r, g, b)))
+        }
+        ColorField::Float => parse_bare_channels(value).map(|[r, g, b]| Srgb::new(r, g, b)),
+        ColorField::Rgb16 => parse_bare_channels(value).map(|channels| {
+            let [r, g, b] = channels.map(|channel| channel / 65535.0);
+            Srgb::new(r, g, b)
+        }),
+        // Read-only; the fallback still takes a color typed over it.
+        ColorField::Description => None,
+    }
+    .map(clamp_srgb)
+    .or_else(|| detect_color(value).map(srgb_from_rgb8))
+}
+
+fn rgb_from_u32(value: u32) -> (u8, u8, u8) {
+    (
+        ((value >> 16) & 0xff) as u8,
+        ((value >> 8) & 0xff) as u8,
+        (value & 0xff) as u8,
+    )
+}
+
+/// CSS name closest to `rgb` by ΔE2000.
+fn nearest_color_name(rgb: (u8, u8, u8)) -> &'static str {
+    let lab = rgb_to_lab(rgb);
+    CSS_COLOR_NAMES
+        .iter()
+        .map(|&(name, value)| (name, delta_e2000(lab, rgb_to_lab(rgb_from_u32(value)))))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(name, _)| name)
+        .unwrap_or("black")
+}
+
+/// Words the CSS names are glued from. Compounds come before their prefixes.
+const COLOR_NAME_WORDS: &[&str] = &[
+    "alice",
+    "almond",
+    "antique",
+    "aquamarine",
+    "aqua",
+    "azure",
+    "beige",
+    "bisque",
+    "black",
+    "blanched",
+    "blue",
+    "blush",
+    "brown",
+    "burly",
+    "cadet",
+    "chartreuse",
+    "chiffon",
+    "chocolate",
+    "coral",
+    "cornflower",
+    "corn",
+    "cream",
+    "crimson",
+    "cyan",
+    "dark",
+    "deep",
+    "dim",
+    "dodger",
+    "drab",
+    "firebrick",
+    "floral",
+    "forest",
+    "fuchsia",
+    "gainsboro",
+    "ghost",
+    "goldenrod",
+    "gold",
+    "gray",
+    "green",
+    "grey",
+    "honeydew",
+    "hot",
+    "indian",
+    "indigo",
+    "ivory",
+    "khaki",
+    "lace",
+    "lavender",
+    "lawn",
+    "lemon",
+    "light",
+    "lime",
+    "linen",
+    "magenta",
+    "maroon",
+    "medium",
+    "midnight",
+    "mint",
+    "misty",
+    "moccasin",
+    "navajo",
+    "navy",
+    "old",
+    "olive",
+    "orange",
+    "orchid",
+    "pale",
+    "papaya",
+    "peach",
+    "peru",
+    "pink",
+    "plum",
+    "powder",
+    "puff",
+    "purple",
+    "rebecca",
+    "red",
+    "rose",
+    "rosy",
+    "royal",
+    "saddle",
+    "salmon",
+    "sandy",
+    "seashell",
+    "sea",
+    "sienna",
+    "silk",
+    "silver",
+    "sky",
+    "slate",
+    "smoke",
+    "snow",
+    "spring",
+    "steel",
+    "tan",
+    "teal",
+    "thistle",
+    "tomato",
+    "turquoise",
+    "violet",
+    "wheat",
+    "whip",
+    "white",
+    "wood",
+    "yellow",
+];
+
+/// Splits a CSS name such as "lightslategray" into "light slate gray" for speech.
+fn spoken_color_name(name: &str) -> String {
+    fn split(rest: &str) -> Option<Vec<&'static str>> {
+        if rest.is_empty() {
+            return Some(Vec::new());
+        }
+        COLOR_NAME_WORDS
+            .iter()
+            .filter(|word| rest.starts_with(**word))
+            .find_map(|word| {
+                let mut words = split(&rest[word.len()..])?;
+                words.insert(0, *word);
+                Some(words)
+            })
+    }
+    split(name)
+        .map(|words| words.join(" "))
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// Screen reader label for a swatch: nearest name and hex.
+fn spoken_color(color: Color) -> String {
+    if color.alpha() == 0 {
+        return tr("No color");
+    }
+    let rgb = (color.red(), color.green(), color.blue());
+    format!(
+        "{}, {}",
+        spoken_color_name(nearest_color_name(rgb)),
+        format_hex(rgb.0, rgb.1, rgb.2)
+    )
+}
+
+fn css_named_color(value: &str) -> Option<(u8, u8, u8)> {
+    let lower = value.trim().to_ascii_lowercase();
+    CSS_COLOR_NAMES
+        .iter()
+        .find(|(name, _)| *name == lower)
+        .map(|(_, hex)| rgb_from_u32(*hex))
+}
+
+/// Parses `0xAARRGGBB` (as used by Android and Qt) and `0xRRGGBB`.
+fn parse_0x_argb(value: &str) -> Option<(u8, u8, u8)> {
+    let trimmed = value.trim();
+    let digits = trimmed
+        .strip_prefix("0x")
+        .or_else(|| trimmed.strip_prefix("0X"))?;
+    if !matches!(digits.len(), 6 | 8) {
+        return None;
+    }
+    u32::from_str_radix(digits, 16).ok().map(rgb_from_u32)
+}
+
+/// Parses bare tuples such as `0.5, 0.2, 0.1` or `[10 20 30]`: floats are 0-1, integers 0-255,
+/// or 16-bit when any of them exceeds 255, as the U16 row writes them.
+fn parse_number_tuple(value: &str) -> Option<(u8, u8, u8)> {
+    let inner = value
+        .trim()
+        .trim_start_matches(['(', '[', '{'])
+        .trim_end_matches([')', ']', '}']);
+    let parts: Vec<&str> = inner
+        .split(|ch: char| ch == ',' || ch.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .collect();
+    if !(3..=4).contains(&parts.len()) {
+        return None;
+    }
+
+    let is_float = parts.iter().any(|part| part.contains('.'));
+    let is_16bit = !is_float
+        && parts[..3]
+            .iter()
+            .any(|part| part.parse::<u32>().is_ok_and(|raw| raw > 255));
+    let channel = |part: &str| -> Option<u8> {
+        let raw = part.trim_end_matches('f').parse::<f32>().ok()?;
+        let scaled = if is_float {
+            raw.clamp(0.0, 1.0) * 255.0
+        } else if is_16bit {
+            raw.clamp(0.0, 65535.0) / 257.0
+        } else {
+            raw.clamp(0.0, 255.0)
+        };
+        Some(scaled.round() as u8)
+    };
+    Some((channel(parts[0])?, channel(parts[1])?, channel(parts[2])?))
+}
+
+/// Detects any supported notation, regardless of which row it was typed into: hex with
+/// 3/4/6/8 digits, color functions, CSS names, `0xAARRGGBB` and number tuples. A CSS
+/// declaration such as `color: #fff;` is reduced to its value.
+fn detect_color(value: &str) -> Option<(u8, u8, u8)> {
+    let trimmed = value.trim().trim_end_matches(';').trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    parse_0x_argb(trimmed)
+        .or_else(|| parse_hex_flexible(trimmed))
+        .or_else(|| parse_css_function(trimmed, None).map(srgb_to_rgb8))
+        .or_else(|| parse_color_function_rgb8(trimmed, None))
+        .or_else(|| css_named_color(trimmed))
+        .or_else(|| parse_number_tuple(trimmed))
+        .or_else(|| {
+            trimmed
+                .split_once(':')
+                .and_then(|(_, declared)| detect_color(declared))
+        })
+}
+
+/// Reads the clipboard text and loads whatever color notation it holds.
+fn paste_color_from_clipboard(ui: &AppWindow, history_store: &HistoryStore) {
+    let ui_weak = ui.as_weak();
+    let history_store = history_store.clone();
+    thread::spawn(move || {
+        let text = match Clipboard::new().and_then(|mut clipboard| clipboard.get_text()) {
+            Ok(text) => text,
+            Err(err) => {
+                eprintln!("Clipboard error: {err}");
+                return;
+            }
+        };
+        let Some((r, g, b)) = detect_color(&text) else {
+            eprintln!("paste: no color found in clipboard text");
+            return;
+        };
+
+        let _ = ui_weak.upgrade_in_event_loop(move |ui| {
+            push_history(&history_store, (r, g, b));
+            sync_history_model(&ui, &history_store);
+            update_ui_colors(&ui, r, g, b);
+            if let Some(note) = srgb_gamut_note(&text, None) {
+                ui.set_color_space_note(note.into());
+            }
+            ui.window().show().ok();
+            persist_config(&ui, &history_store);
+        });
+    });
+}
+
+/// Shows the history entry `delta` places from the selected one; without a selection the
+/// newest entry is shown.
+fn step_history_selection(ui: &AppWindow, history_store: &HistoryStore, delta: i32) {
+    let (index, (r, g, b)) = {
+        let guard = history_store.lock().unwrap();
+        if guard.is_empty() {
+            return;
+        }
+        let selected = ui.get_history_selected();
+        let index = if selected < 0 {
+            0
+        } else {
+            (selected + delta).clamp(0, guard.len() as i32 - 1)
+        };
+        (index, guard[index as usize])
+    };
+    update_ui_colors(ui, r, g, b);
+    ui.set_history_selected(index);
+    record_undo_step(ui, history_store);
+}
+
+/// Removes the selected history entry, or the current color's entry when none is
+/// selected, and moves on to its neighbour.
+fn remove_history_entry(ui: &AppWindow, history_store: &HistoryStore) {
+    let current = srgb_to_rgb8(current_color());
+    let next = {
+        let mut guard = history_store.lock().unwrap();
+        let selected = ui.get_history_selected();
+        let index = if selected >= 0 {
+            selected as usize
+        } else {
+            match guard.iter().position(|rgb| *rgb == current) {
+                Some(index) => index,
+                None => return,
+            }
+        };
+        if index >= guard.len() {
+            return;
+        }
+        guard.remove(index);
+        let next = index.min(guard.len().saturating_sub(1));
+        guard.get(next).map(|rgb| (next, *rgb))
+    };
+
+    sync_history_model(ui, history_store);
+    if let Some((index, (r, g, b))) = next {
+        update_ui_colors(ui, r, g, b);
+        ui.set_history_selected(index as i32);
+    }
+    persist_config(ui, history_store);
+}
+
+/// Subsequence match of `query` in `text`, ignoring case and spaces in the query. Runs of
+/// consecutive characters and matches at word starts score higher; gaps cost a little.
+fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+    for wanted in query
+        .to_lowercase()
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+    {
+        let found = (position..text.len()).find(|&index| text[index] == wanted)?;
+        score += 1;
+        if previous.is_some_and(|previous| previous + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        score -= (found - position).min(3) as i32;
+        previous = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
+
+fn command_item(
+    label: String,
+    detail: impl Into<SharedString>,
+    swatch: Option<(u8, u8, u8)>,
+) -> CommandItem {
+    CommandItem {
+        label: label.into(),
+        detail: detail.into(),
+        swatch: swatch.map_or(Color::default(), |(r, g, b)| Color::from_rgb_u8(r, g, b)),
+        has_swatch: swatch.is_some(),
+    }
+}
+
+/// Everything the command palette offers: actions first, then palette and named colors.
+fn command_candidates(ui: &AppWindow) -> Vec<(AppCommand, CommandItem)> {
+    let mut candidates = Vec::new();
+    let actions = [
+        HotkeyAction::Pick,
+        HotkeyAction::PickOpenDetails,
+        HotkeyAction::PasteColor,
+        HotkeyAction::CopyLast,
+        HotkeyAction::OpenImage,
+        HotkeyAction::ExtractPalette,
+        HotkeyAction::PickGradient,
+        HotkeyAction::WatchPoint,
+    ];
+    for action in actions {
+        candidates.push((
+            AppCommand::Action(action),
+            command_item(action.description(), tr("Action"), None),
+        ));
+    }
+    for field in COPY_FORMATS {
+        candidates.push((
+            AppCommand::Copy(field),
+            command_item(
+                tr_format("Copy as {}", &[&field.ui_label()]),
+                value_row_text(ui, field),
+                None,
+            ),
+        ));
+    }
+    candidates.push((AppCommand::Undo, command_item(tr("Undo"), "Ctrl+Z", None)));
+    candidates.push((
+        AppCommand::Redo,
+        command_item(tr("Redo"), "Ctrl+Shift+Z", None),
+    ));
+    candidates.push((
+        AppCommand::ClearHistory,
+        command_item(tr("Clear history"), "", None),
+    ));
+    for field in COPY_FORMATS {
+        let action = HotkeyAction::PickCopy(field);
+        candidates.push((
+            AppCommand::Action(action),
+            command_item(action.description(), tr("Action"), None),
+        ));
+    }
+    for (index, color) in ui.get_palette_model().iter().enumerate() {
+        let rgb = (color.red(), color.green(), color.blue());
+        candidates.push((
+            AppCommand::PaletteColor(index),
+            command_item(
+                tr_format("Palette color {}", &[&(index + 1)]),
+                format_hex(rgb.0, rgb.1, rgb.2),
+                Some(rgb),
+            ),
+        ));
+    }
+    for &(name, value) in CSS_COLOR_NAMES {
+        let rgb = rgb_from_u32(value);
+        candidates.push((
+            AppCommand::NamedColor(value),
+            command_item(name.to_string(), format_hex(rgb.0, rgb.1, rgb.2), Some(rgb)),
+        ));
+    }
+    candidates
+}
+
+/// Best command palette matches for `query`, scored on the label and the detail text.
+fn search_commands(ui: &AppWindow, query: &str) -> Vec<(AppCommand, CommandItem)> {
+    let mut scored: Vec<(i32, (AppCommand, CommandItem))> = command_candidates(ui)
+        .into_iter()
+        .filter_map(|candidate| {
+            let item = &candidate.1;
+            let score = fuzzy_score(query, &item.label).max(fuzzy_score(query, &item.detail))?;
+            Some((score, candidate))
+        })
+        .collect();
+    scored.sort_by_key(|entry| std::cmp::Reverse(entry.0));
+    scored
+        .into_iter()
+        .take(COMMAND_PALETTE_RESULTS)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+fn run_app_command(ui: &AppWindow, history_store: &HistoryStore, command: AppCommand) {
+    match command {
+        AppCommand::Action(action) => run_hotkey_action(ui, history_store, action),
+        AppCommand::Copy(field) => ui.invoke_copy_to_clipboard(value_row_text(ui, field)),
+        AppCommand::Undo => ui.invoke_undo(),
+        AppCommand::Redo => ui.invoke_redo(),
+        AppCommand::ClearHistory => ui.invoke_clear_history(),
+        AppCommand::PaletteColor(index) => ui.invoke_palette_clicked(index as i32),
+        AppCommand::NamedColor(value) => {
+            let (r, g, b) = rgb_from_u32(value);
+            push_history(history_store, (r, g, b));
+            sync_history_model(ui, history_store);
+            update_ui_colors(ui, r, g, b);
+            persist_config(ui, history_store);
+        }
+    }
+}
+
+fn sync_history_model(ui: &AppWindow, history_store: &HistoryStore) {
+    let colors: Vec<Color> = {
+        let guard = history_store.lock().unwrap();
+        guard
+            .iter()
+            .map(|(r, g, b)| Color::from_rgb_u8(*r, *g, *b))
+            .collect()
+    };
+    ui.set_history_model(ModelRc::from(Rc::new(VecModel::from(colors))));
+    sync_tray(ui, history_store);
+}
+
+fn set_palette_model(ui: &AppWindow, colors: impl Iterator<Item = (u8, u8, u8)>) {
+    let colors: Vec<Color> = colors
+        .map(|(r, g, b)| Color::from_rgb_u8(r, g, b))
+        .collect();
+    ui.set_palette_model(ModelRc::from(Rc::new(VecModel::from(colors))));
+}
+
+fn add_to_palette(ui: &AppWindow, history_store: &HistoryStore, rgb: (u8, u8, u8)) {
+    let mut colors: Vec<(u8, u8, u8)> = ui
+        .get_palette_model()
+        .iter()
+        .map(|color| (color.red(), color.green(), color.blue()))
+        .collect();
+    if colors.contains(&rgb) {
+        return;
+    }
+    colors.push(rgb);
+    set_palette_model(ui, colors.into_iter());
+    persist_config(ui, history_store);
+}
+
+fn push_history(history_store: &HistoryStore, rgb: (u8, u8, u8)) {
+    let mut guard = history_store.lock().unwrap();
+    guard.insert(0, rgb);
+    PENDING_PICKS.lock().unwrap().insert(0, rgb);
+}
+
+fn configured_copy_format(ui: &AppWindow) -> ColorField {
+    ColorField::from_ui_label(&ui.get_setting_copy_format()).unwrap_or(ColorField::Hex)
+}
+
+fn configured_clipboard_style(ui: &AppWindow) -> ClipboardStyle {
+    ClipboardStyle::from_ui_label(&ui.get_setting_clipboard_style()).unwrap_or(ClipboardStyle::Text)
+}
+
+fn configured_color_space(ui: &AppWindow) -> ReportSpace {
+    ReportSpace::from_ui_label(&ui.get_setting_color_space()).unwrap_or(ReportSpace::Srgb)
+}
+
+fn send_clipboard_request(request: ClipboardRequest) {
+    let sender = CLIPBOARD_OWNER.get_or_init(|| {
+        let (sender, requests) = mpsc::channel();
+        thread::spawn(move || run_clipboard_owner(requests));
+        sender
+    });
+    if sender.send(request).is_err() {
+        eprintln!("Clipboard error: owner thread is gone");
+    }
+}
+
+/// Copies `text` for the color `rgb`, adding rich content per the clipboard style.
+fn copy_color_async(text: String, rgb: (u8, u8, u8)) {
+    send_clipboard_request(ClipboardRequest::Copy { text, rgb });
+}
+
+fn copy_text_async(text: String) {
+    send_clipboard_request(ClipboardRequest::Text(text));
+}
+
+/// Hands clipboard ownership to the clipboard manager so copies outlive the app.
+fn shutdown_clipboard_owner() {
+    let Some(sender) = CLIPBOARD_OWNER.get() else {
+        return;
+    };
+    let (done_tx, done_rx) = mpsc::channel();
+    if sender.send(ClipboardRequest::Shutdown(done_tx)).is_ok() {
+        let _ = done_rx.recv_timeout(Duration::from_secs(2));
+    }
+}
+
+/// Keeps one `Clipboard` alive for the whole session: on X11 the selection is served by
+/// its owner, and dropping the last instance hands the content to the clipboard manager.
+fn run_clipboard_owner(requests: mpsc::Receiver<ClipboardRequest>) {
+    let mut clipboard = match Clipboard::new() {
+        Ok(clipboard) => clipboard,
+        Err(err) => {
+            eprintln!("Clipboard error: {err}");
+            return;
+        }
+    };
+    let mut style = ClipboardStyle::Text;
+
+    while let Ok(request) = requests.recv() {
+        match request {
+            ClipboardRequest::Style(next) => style = next,
+            ClipboardRequest::Text(text) => {
+                if let Err(err) = clipboard.set_text(text) {
+                    eprintln!("Clipboard error: {err}");
+                }
+            }
+            ClipboardRequest::Copy { text, rgb } => {
+                let result = match style {
+                    ClipboardStyle::Text => clipboard.set_text(text),
+                    ClipboardStyle::Html => {
+                        clipboard.set_html(clipboard_swatch_html(&text, rgb), Some(text))
+                    }
+                    ClipboardStyle::Image => {
+                        let (r, g, b) = rgb;
+                        clipboard.set_image(ImageData {
+                            width: CLIPBOARD_SWATCH_SIZE,
+                            height: CLIPBOARD_SWATCH_SIZE,
+                            bytes: [r, g, b, 255]
+                                .repeat(CLIPBOARD_SWATCH_SIZE * CLIPBOARD_SWATCH_SIZE)
+                                .into(),
+                        })
+                    }
+                };
+                if let Err(err) = result {
+                    eprintln!("Clipboard error: {err}");
+                }
+            }
+            ClipboardRequest::Shutdown(done) => {
+                drop(clipboard);
+                let _ = done.send(());
+                return;
+            }
+        }
+    }
+}
+
+fn clipboard_swatch_html(text: &str, rgb: (u8, u8, u8)) -> String {
+    let hex = format_canonical(ColorField::Hex, rgb);
+    let escaped = text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    format!(
+        "<span style=\"display:inline-block;width:1em;height:1em;background:{hex};border:1px solid #0003;vertical-align:middle\"></span> <code>{escaped}</code>"
+    )
+}
+
+fn apply_selected_color(
+    ui: &AppWindow,
+    history_store: &HistoryStore,
+    outcome: PickOutcome,
+    color: Srgb,
+) {
+    let (r, g, b) = srgb_to_rgb8(fit_srgb(color));
+    if outcome == PickOutcome::Reference {
+        set_compare_reference(ui, (r, g, b));
+        ui.window().show().ok();
+        return;
+    }
+
+    push_history(history_store, (r, g, b));
+    sync_history_model(ui, history_store);
+    update_ui_colors_precise(ui, color);
+
+    if let Some(field) = outcome.copy_field(ui) {
+        copy_color_async(format_color(field, color), (r, g, b));
+    } else {
+        ui.window().show().ok();
+    }
+
+    persist_config(ui, history_store);
+}
+
+fn apply_region_palette(ui: &AppWindow, history_store: &HistoryStore, colors: &[(u8, u8, u8)]) {
+    let Some(&(r, g, b)) = colors.first() else {
+        return;
+    };
+    for rgb in colors.iter().rev() {
+        push_history(history_store, *rgb);
+    }
+    sync_history_model(ui, history_store);
+    update_ui_colors(ui, r, g, b);
+    ui.window().show().ok();
+    persist_config(ui, history_store);
+}
+
+/// Dominant colors of the BGRA frame inside the rectangle spanned by `from` and `to`,
+/// most common first. Pixels are grouped by their top 4 bits per channel.
+fn extract_palette(
+    frame: &[u8],
+    width: i32,
+    height: i32,
+    from: (i32, i32),
+    to: (i32, i32),
+) -> Vec<(u8, u8, u8)> {
+    let x0 = from.0.min(to.0).clamp(0, width - 1);
+    let x1 = from.0.max(to.0).clamp(0, width - 1);
+    let y0 = from.1.min(to.1).clamp(0, height - 1);
+    let y1 = from.1.max(to.1).clamp(0, height - 1);
+
+    // Sample at most ~250k pixels so large regions stay fast.
+    let area = (x1 - x0 + 1) as f64 * (y1 - y0 + 1) as f64;
+    let step = ((area / 250_000.0).sqrt().ceil() as usize).max(1);
+    let stride = width as usize * 4;
+
+    let mut buckets: HashMap<u16, [u64; 4]> = HashMap::new();
+    for y in (y0..=y1).step_by(step) {
+        for x in (x0..=x1).step_by(step) {
+            let idx = y as usize * stride + x as usize * 4;
+            if idx + 2 >= frame.len() {
+                continue;
+            }
+            let (b, g, r) = (frame[idx], frame[idx + 1], frame[idx + 2]);
+            let key = ((r >> 4) as u16) << 8 | ((g >> 4) as u16) << 4 | (b >> 4) as u16;
+            let bucket = buckets.entry(key).or_default();
+            bucket[0] += r as u64;
+            bucket[1] += g as u64;
+            bucket[2] += b as u64;
+            bucket[3] += 1;
+        }
+    }
+
+    let mut ranked: Vec<[u64; 4]> = buckets.into_values().collect();
+    ranked.sort_by(|left, right| right[3].cmp(&left[3]));
+    ranked
+        .into_iter()
+        .take(PALETTE_MAX_COLORS)
+        .map(|[r, g, b, n]| ((r / n) as u8, (g / n) as u8, (b / n) as u8))
+        .collect()
+}
+
+fn capture_region_palette(
+    capturer: &mut Capturer,
+    a: (i32, i32),
+    b: (i32, i32),
+) -> Vec<(u8, u8, u8)> {
+    let width = capturer.width() as i32;
+    let height = capturer.height() as i32;
+    if width <= 0 || height <= 0 {
+        return vec![];
+    }
+
+    for _ in 0..30 {
+        match capturer.frame() {
+            Ok(frame) => return extract_palette(&frame, width, height, a, b),
+            Err(err) if err.kind() == ErrorKind::WouldBlock => {
+                thread::sleep(Duration::from_millis(16));
+            }
+            Err(err) => {
+                eprintln!("x11 picker: region capture error: {err:?}");
+                return vec![];
+            }
+        }
+    }
+    vec![]
+}
+
+/// Color of the BGRA frame pixel at `x`,`y`, clamped to the frame.
+fn frame_pixel(frame: &[u8], width: i32, height: i32, x: i32, y: i32) -> Option<(u8, u8, u8)> {
+    if width <= 0 || height <= 0 {
+        return None;
+    }
+
+    let safe_x = x.clamp(0, width - 1);
+    let safe_y = y.clamp(0, height - 1);
+    let idx = safe_y as usize * width as usize * 4 + safe_x as usize * 4;
+    if idx + 2 < frame.len() {
+        Some((frame[idx + 2], frame[idx + 1], frame[idx]))
+    } else {
+        None
+    }
+}
+
+/// The `_ICC_PROFILE` property of the root window, as set by colord, xiccd or dispwin.
+fn x11_icc_profile() -> Result<Vec<u8>, String> {
+    let (conn, screen_num) =
+        x11rb::connect(None).map_err(|err| format!("x11 connect failed: {err}"))?;
+    let root = conn.setup().roots[screen_num].root;
+    let atom = conn
+        .intern_atom(true, b"_ICC_PROFILE")
+        .map_err(|err| format!("intern_atom failed: {err}"))?
+        .reply()
+        .map_err(|err| format!("intern_atom reply failed: {err}"))?
+        .atom;
+    if atom == NONE {
+        return Err("no _ICC_PROFILE atom".to_string());
+    }
+    let reply = conn
+        .get_property(false, root, atom, AtomEnum::ANY, 0, u32::MAX / 4)
+        .map_err(|err| format!("get_property failed: {err}"))?
+        .reply()
+        .map_err(|err| format!("get_property reply failed: {err}"))?;
+    if reply.value.is_empty() {
+        return Err("_ICC_PROFILE is empty".to_string());
+    }
+    Ok(reply.value)
+}
+
+/// Default profile of the first display device colord knows about.
+fn colord_icc_profile() -> Result<Vec<u8>, String> {
+    const COLORD: &str = "org.freedesktop.ColorManager";
+    let connection =
+        ZbusConnection::system().map_err(|err| format!("system bus unavailable: {err}"))?;
+    let manager = ZbusProxy::new(&connection, COLORD, "/org/freedesktop/ColorManager", COLORD)
+        .map_err(|err| format!("colord proxy failed: {err}"))?;
+    let reply = manager
+        .call_method("GetDevicesByKind", &("display",))
+        .map_err(|err| format!("GetDevicesByKind failed: {err}"))?;
+    let (devices,): (Vec<OwnedObjectPath>,) = reply
+        .body()
+        .deserialize()
+        .map_err(|err| format!("GetDevicesByKind decode failed: {err}"))?;
+    let device = devices.first().ok_or("colord knows no display")?;
+    let device = ZbusProxy::new(
+        &connection,
+        COLORD,
+        device.as_str(),
+        "org.freedesktop.ColorManager.Device",
+    )
+    .map_err(|err| format!("device proxy failed: {err}"))?;
+    let profiles: Vec<OwnedObjectPath> = device
+        .get_property("Profiles")
+        .map_err(|err| format!("device Profiles failed: {err}"))?;
+    let profile = profiles.first().ok_or("display has no profile assigned")?;
+    let profile = ZbusProxy::new(
+        &connection,
+        COLORD,
+        profile.as_str(),
+        "org.freedesktop.ColorManager.Profile",
+    )
+    .map_err(|err| format!("profile proxy failed: {err}"))?;
+    let filename: String = profile
+        .get_property("Filename")
+        .map_err(|err| format!("profile Filename failed: {err}"))?;
+    fs::read(&filename).map_err(|err| format!("could not read {filename}: {err}"))
+}
+
+fn display_icc_profile() -> Option<Profile> {
+    let data = match x11_icc_profile() {
+        Ok(data) => data,
+        Err(x11_err) => match colord_icc_profile() {
+            Ok(data) => data,
+            Err(colord_err) => {
+                eprintln!("icc: no display profile ({x11_err}; colord: {colord_err})");
+                return None;
+            }
+        },
+    };
+    match Profile::new_icc(&data) {
+        Ok(profile) => Some(profile),
+        Err(err) => {
+            eprintln!("icc: display profile is invalid: {err}");
+            None
+        }
+    }
+}
+
+/// Display P3: P3 primaries, D65 white and the sRGB transfer curve.
+fn display_p3_profile() -> Option<Profile> {
+    let white = CIExyY {
+        x: 0.3127,
+        y: 0.3290,
+        Y: 1.0,
+    };
+    let primaries = CIExyYTRIPLE {
+        Red: CIExyY {
+            x: 0.680,
+            y: 0.320,
+            Y: 1.0,
+        },
+        Green: CIExyY {
+            x: 0.265,
+            y: 0.690,
+            Y: 1.0,
+        },
+        Blue: CIExyY {
+            x: 0.150,
+            y: 0.060,
+            Y: 1.0,
+        },
+    };
+    let curve =
+        ToneCurve::new_parametric(4, &[2.4, 1.0 / 1.055, 0.055 / 1.055, 1.0 / 12.92, 0.04045])
+            .ok()?;
+    Profile::new_rgb(&white, &primaries, &[&curve, &curve, &curve]).ok()
+}
+
+/// Converts captured display RGB to the sRGB that history, previews and every format store.
+/// Through Display P3 the result keeps channels outside 0-1, so the P3 and Rec. 2020 rows
+/// can show colors beyond sRGB; `fit_srgb` maps them for everything else.
+struct DisplayColorTransform {
+    transform: Transform<[u8; 3], [f32; 3]>,
+    space: ReportSpace,
+    /// Shown next to converted values, e.g. "ICC: DELL U2720Q → sRGB".
+    note: String,
+}
+
+impl DisplayColorTransform {
+    fn new(space: ReportSpace) -> Option<Self> {
+        let target = match space {
+            ReportSpace::Display => return None,
+            ReportSpace::Srgb => Profile::new_srgb(),
+            ReportSpace::DisplayP3 => display_p3_profile()?,
+        };
+        let display = display_icc_profile()?;
+        let name = display
+            .info(InfoType::Description, Locale::none())
+            .unwrap_or_else(|| "display".to_string());
+        let transform = match Transform::new(
+            &display,
+            PixelFormat::RGB_8,
+            &target,
+            PixelFormat::RGB_FLT,
+            Intent::RelativeColorimetric,
+        ) {
+            Ok(transform) => transform,
+            Err(err) => {
+                eprintln!("icc: could not build transform: {err}");
+                return None;
+            }
+        };
+        let note = match space {
+            ReportSpace::DisplayP3 => format!("ICC: {name} → Display P3 → sRGB"),
+            _ => format!("ICC: {name} → {}", space.ui_label()),
+        };
+        Some(Self {
+            transform,
+            space,
+            note,
+        })
+    }
+
+    fn apply(&self, (r, g, b): (u8, u8, u8)) -> Srgb {
+        let mut out = [[0.0f32; 3]];
+        self.transform.transform_pixels(&[[r, g, b]], &mut out);
+        let [r, g, b] = out[0];
+        match self.space {
+            ReportSpace::DisplayP3 => {
+                srgb_unclamped(CssColorSpace::DisplayP3.to_linear_srgb([r, g, b]))
+            }
+            _ => clamp_srgb(Srgb::new(r, g, b)),
+        }
+    }
+
+    /// `apply` as the 8-bit sRGB that history, palettes and the overlay use.
+    fn apply_rgb8(&self, rgb: (u8, u8, u8)) -> (u8, u8, u8) {
+        srgb_to_rgb8(fit_srgb(self.apply(rgb)))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct WatchSample {
+    elapsed: Duration,
+    rgb: (u8, u8, u8),
+    lightness: f32,
+}
+
+fn rgb_to_lab(rgb: (u8, u8, u8)) -> Lab {
+    Srgb::new(rgb.0, rgb.1, rgb.2)
+        .into_format::<f32>()
+        .into_color()
+}
+
+fn cie_lightness(rgb: (u8, u8, u8)) -> f32 {
+    rgb_to_lab(rgb).l
+}
+
+fn with_watcher_window<R>(f: impl FnOnce(&mut Option<WatcherWindow>) -> R) -> R {
+    WATCHER_WINDOW.with(|slot| {
+        let mut window = slot.borrow_mut();
+        f(&mut window)
+    })
+}
+
+fn ensure_watcher_window() -> Result<slint::Weak<WatcherWindow>, slint::PlatformError> {
+    with_watcher_window(|slot| {
+        if slot.is_none() {
+            let window = WatcherWindow::new()?;
+            window.window().on_close_requested(move || {
+                POINT_WATCH_STOP.store(true, Ordering::SeqCst);
+                slint::CloseRequestResponse::HideWindow
+            });
+            *slot = Some(window);
+        }
+
+        Ok(slot.as_ref().expect("watcher window must exist").as_weak())
+    })
+}
+
+/// SVG path of the last `WATCH_GRAPH_SAMPLES` values, each already scaled to 0-100.
+fn watch_graph_path(
+    samples: &VecDeque<WatchSample>,
+    value: impl Fn(&WatchSample) -> f32,
+) -> String {
+    samples
+        .range(samples.len().saturating_sub(WATCH_GRAPH_SAMPLES)..)
+        .enumerate()
+        .map(|(index, sample)| {
+            let command = if index == 0 { 'M' } else { 'L' };
+            format!(
+                "{command} {index} {:.1}",
+                100.0 - value(sample).clamp(0.0, 100.0)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `~/archtoys-<kind>-<unix time>.csv`, where CSV exports land.
+fn csv_export_path(kind: &str) -> Result<PathBuf, String> {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+    let home = std::env::var("HOME").map_err(|_| "HOME is not set".to_string())?;
+    Ok(PathBuf::from(home).join(format!("archtoys-{kind}-{stamp}.csv")))
+}
+
+fn export_watch_csv(point: (i32, i32), samples: &VecDeque<WatchSample>) -> Result<PathBuf, String> {
+    let path = csv_export_path("watch")?;
+
+    let mut csv = String::from("elapsed_ms,x,y,hex,r,g,b,lightness\n");
+    for sample in samples {
+        let (r, g, b) = sample.rgb;
+        csv.push_str(&format!(
+            "{},{},{},{},{r},{g},{b},{:.2}\n",
+            sample.elapsed.as_millis(),
+            point.0,
+            point.1,
+            format_hex(r, g, b),
+            sample.lightness
+        ));
+    }
+
+    fs::write(&path, csv).map_err(|err| format!("{}: {err}", path.display()))?;
+    Ok(path)
+}
+
+/// Logs a change of the watched color and runs `watch_command`, if any, with the colors
+/// in `ARCHTOYS_WATCH_*` environment variables.
+fn run_watch_trigger(command: &str, point: (i32, i32), before: (u8, u8, u8), after: (u8, u8, u8)) {
+    let before_hex = format_hex(before.0, before.1, before.2);
+    let after_hex = format_hex(after.0, after.1, after.2);
+    eprintln!(
+        "watch: ({}, {}) changed {before_hex} -> {after_hex}",
+        point.0, point.1
+    );
+
+    if command.trim().is_empty() {
+        return;
+    }
+    let spawned = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("ARCHTOYS_WATCH_X", point.0.to_string())
+        .env("ARCHTOYS_WATCH_Y", point.1.to_string())
+        .env("ARCHTOYS_WATCH_BEFORE", &before_hex)
+        .env("ARCHTOYS_WATCH_AFTER", &after_hex)
+        .spawn();
+    match spawned {
+        Ok(mut child) => {
+            thread::spawn(move || {
+                let _ = child.wait();
+            });
+        }
+        Err(err) => eprintln!("watch: failed to run trigger command: {err}"),
+    }
+}
+
+/// Opens the watcher window and samples `point` until it is stopped or closed.
+fn start_point_watch(ui: &AppWindow, point: (i32, i32)) {
+    if POINT_WATCH_ACTIVE
+        .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+        .is_err()
+    {
+        eprintln!("watch: a point is already being watched; stop it first");
+        return;
+    }
+    POINT_WATCH_STOP.store(false, Ordering::SeqCst);
+
+    let window_weak = match ensure_watcher_window() {
+        Ok(window_weak) => window_weak,
+        Err(err) => {
+            eprintln!("watch: failed to create watcher window: {err:?}");
+            POINT_WATCH_ACTIVE.store(false, Ordering::SeqCst);
+            return;
+        }
+    };
+
+    let cfg = synced_config();
+    let color_space = configured_color_space(ui);
+    let interval = Duration::from_millis(cfg.watch_interval_ms.max(10));
+    let samples: Arc<Mutex<VecDeque<WatchSample>>> = Arc::new(Mutex::new(VecDeque::new()));
+
+    if let Some(window) = window_weak.upgrade() {
+        copy_skin(ui, &window.global::<Skin>());
+        window.set_point_text(format!("x {}, y {}", point.0, point.1).into());
+        window.set_status_text(tr_format("Sampling every {} ms", &[&interval.as_millis()]).into());
+        window.set_graph_span(WATCH_GRAPH_SAMPLES as i32 - 1);
+        window.set_running(true);
+
+        let export_samples = samples.clone();
+        let export_weak = window.as_weak();
+        window.on_export_csv(move || {
+            let snapshot = export_samples.lock().unwrap().clone();
+            let status = match export_watch_csv(point, &snapshot) {
+                Ok(path) => tr_format("Saved {}", &[&path.display()]),
+                Err(err) => {
+                    eprintln!("watch: CSV export failed: {err}");
+                    tr_format("Export failed: {}", &[&err])
+                }
+            };
+            if let Some(window) = export_weak.upgrade() {
+                window.set_status_text(status.into());
+            }
+        });
+        window.on_stop_watch(|| POINT_WATCH_STOP.store(true, Ordering::SeqCst));
+        window.show().ok();
+    }
+
+    thread::spawn(move || {
+        let capturer = Display::main().and_then(Capturer::new);
+        let mut capturer = match capturer {
+            Ok(capturer) => Some(capturer),
+            Err(err) => {
+                eprintln!("watch: could not start screen capture: {err:?}");
+                None
+            }
+        };
+        let color_transform = DisplayColorTransform::new(color_space);
+
+        let started = Instant::now();
+        let mut baseline: Option<(u8, u8, u8)> = None;
+        while let Some(capturer) = capturer.as_mut() {
+            if POINT_WATCH_STOP.load(Ordering::SeqCst) {
+                break;
+            }
+
+            let width = capturer.width() as i32;
+            let height = capturer.height() as i32;
+            let rgb = match capturer.frame() {
+                Ok(frame) => frame_pixel(&frame, width, height, point.0, point.1).map(|rgb| {
+                    match &color_transform {
+                        Some(transform) => transform.apply_rgb8(rgb),
+                        None => rgb,
+                    }
+                }),
+                Err(err) if err.kind() == ErrorKind::WouldBlock => {
+                    thread::sleep(Duration::from_millis(5));
+                    continue;
+                }
+                Err(err) => {
+                    eprintln!("watch: capture error: {err:?}");
+                    break;
+                }
+            };
+            let Some(rgb) = rgb else {
+                break;
+            };
+
+            let sample = WatchSample {
+                elapsed: started.elapsed(),
+                rgb,
+                lightness: cie_lightness(rgb),
+            };
+
+            if cfg.watch_threshold > 0 {
+                let before = *baseline.get_or_insert(rgb);
+                let delta = [
+                    before.0.abs_diff(rgb.0),
+                    before.1.abs_diff(rgb.1),
+                    before.2.abs_diff(rgb.2),
+                ];
+                if delta.into_iter().max().unwrap_or(0) > cfg.watch_threshold {
+                    run_watch_trigger(&cfg.watch_command, point, before, rgb);
+                    baseline = Some(rgb);
+                }
+            }
+
+            let (red, green, blue, lightness) = {
+                let mut samples = samples.lock().unwrap();
+                if samples.len() >= WATCH_MAX_SAMPLES {
+                    samples.pop_front();
+                }
+                samples.push_back(sample);
+                let channel = |value: u8| value as f32 / 255.0 * 100.0;
+                (
+                    watch_graph_path(&samples, |s| channel(s.rgb.0)),
+                    watch_graph_path(&samples, |s| channel(s.rgb.1)),
+                    watch_graph_path(&samples, |s| channel(s.rgb.2)),
+                    watch_graph_path(&samples, |s| s.lightness),
+                )
+            };
+
+            let window_weak = window_weak.clone();
+            let _ = slint::invoke_from_event_loop(move || {
+                let Some(window) = window_weak.upgrade() else {
+                    return;
+                };
+                let (r, g, b) = rgb;
+                window.set_current_color(Color::from_rgb_u8(r, g, b));
+                window.set_current_hex(
+                    format!(
+                        "{}  L* {}",
+                        format_hex(r, g, b),
+                        localize_decimal(format!("{:.1}", sample.lightness))
+                    )
+                    .into(),
+                );
+                window.set_red_path(red.into());
+                window.set_green_path(green.into());
+                window.set_blue_path(blue.into());
+                window.set_lightness_path(lightness.into());
+            });
+
+            thread::sleep(interval);
+        }
+
+        POINT_WATCH_ACTIVE.store(false, Ordering::SeqCst);
+        let _ = slint::invoke_from_event_loop(move || {
+            if let Some(window) = window_weak.upgrade() {
+                window.set_running(false);
+                window.set_status_text(tr("Stopped").into());
+            }
+        });
+    });
+}
+
+/// `GRADIENT_SAMPLES` evenly spaced colors of the BGRA frame from `from` to `to`.
+fn sample_line(
+    frame: &[u8],
+    width: i32,
+    height: i32,
+    from: (i32, i32),
+    to: (i32, i32),
+) -> Vec<(u8, u8, u8)> {
+    (0..GRADIENT_SAMPLES)
+        .filter_map(|index| {
+            let t = index as f32 / (GRADIENT_SAMPLES - 1) as f32;
+            let x = from.0 as f32 + (to.0 - from.0) as f32 * t;
+            let y = from.1 as f32 + (to.1 - from.1) as f32 * t;
+            frame_pixel(frame, width, height, x.round() as i32, y.round() as i32)
+        })
+        .collect()
+}
+
+fn capture_line_samples(
+    capturer: &mut Capturer,
+    from: (i32, i32),
+    to: (i32, i32),
+) -> Vec<(u8, u8, u8)> {
+    let width = capturer.width() as i32;
+    let height = capturer.height() as i32;
+
+    for _ in 0..30 {
+        match capturer.frame() {
+            Ok(frame) => return sample_line(&frame, width, height, from, to),
+            Err(err) if err.kind() == ErrorKind::WouldBlock => {
+                thread::sleep(Duration::from_millis(16));
+            }
+            Err(err) => {
+                eprintln!("x11 picker: line capture error: {err:?}");
+                return vec![];
+            }
+        }
+    }
+    vec![]
+}
+
+/// CSS gradient angle of a screen-space line: 0deg points up, 90deg to the right.
+fn css_gradient_angle(from: (i32, i32), to: (i32, i32)) -> f32 {
+    let (dx, dy) = ((to.0 - from.0) as f32, (to.1 - from.1) as f32);
+    dx.atan2(-dy).to_degrees().rem_euclid(360.0).round()
+}
+
+/// Fewest of the evenly spaced `samples` whose linear interpolation stays within
+/// `GRADIENT_TOLERANCE` of every sample, as (position 0-1, color) stops.
+fn reduce_gradient_stops(samples: &[(u8, u8, u8)]) -> Vec<(f32, (u8, u8, u8))> {
+    let count = samples.len();
+    if count < 2 {
+        return samples.iter().map(|rgb| (0.0, *rgb)).collect();
+    }
+
+    let fits = |from: usize, to: usize| {
+        (from + 1..to).all(|index| {
+            let t = (index - from) as f32 / (to - from) as f32;
+            let (start, end, actual) = (samples[from], samples[to], samples[index]);
+            let error =
+                |a: u8, b: u8, c: u8| (a as f32 + (b as f32 - a as f32) * t - c as f32).abs();
+            error(start.0, end.0, actual.0) <= GRADIENT_TOLERANCE
+                && error(start.1, end.1, actual.1) <= GRADIENT_TOLERANCE
+                && error(start.2, end.2, actual.2) <= GRADIENT_TOLERANCE
+        })
+    };
+
+    // stops[to]: fewest stops covering samples[..=to] that end on `to`; previous links back.
+    let mut stops = vec![usize::MAX; count];
+    let mut previous = vec![0; count];
+    stops[0] = 1;
+    for to in 1..count {
+        for from in 0..to {
+            if stops[from] + 1 < stops[to] && fits(from, to) {
+                stops[to] = stops[from] + 1;
+                previous[to] = from;
+            }
+        }
+    }
+
+    let mut kept = vec![count - 1];
+    while let Some(&last) = kept.last() {
+        if last == 0 {
+            break;
+        }
+        kept.push(previous[last]);
+    }
+    kept.reverse();
+    kept.into_iter()
+        .map(|index| (index as f32 / (count - 1) as f32, samples[index]))
+        .collect()
+}
+
+fn css_linear_gradient(stops: &[(f32, (u8, u8, u8))], angle: f32) -> String {
+    let stops: Vec<String> = stops
+        .iter()
+        .map(|(position, (r, g, b))| {
+            let percent = (position * 1000.0).round() / 10.0;
+            format!("{} {percent}%", format_hex(*r, *g, *b))
+        })
+        .collect();
+    format!("linear-gradient({angle}deg, {})", stops.join(", "))
+}
+
+fn gradient_samples(ui: &AppWindow) -> Vec<(u8, u8, u8)> {
+    ui.get_gradient_model()
+        .iter()
+        .map(|color| (color.red(), color.green(), color.blue()))
+        .collect()
+}
+
+fn apply_gradient_samples(ui: &AppWindow, colors: &[(u8, u8, u8)], angle: f32) {
+    let colors: Vec<Color> = colors
+        .iter()
+        .map(|&(r, g, b)| Color::from_rgb_u8(r, g, b))
+        .collect();
+    ui.set_gradient_model(ModelRc::from(Rc::new(VecModel::from(colors))));
+    ui.set_gradient_angle(angle);
+    ui.window().show().ok();
+}
+
+/// CIE76: straight Euclidean distance in Lab.
+fn delta_e76(reference: Lab, sample: Lab) -> f32 {
+    ((reference.l - sample.l).powi(2)
+        + (reference.a - sample.a).powi(2)
+        + (reference.b - sample.b).powi(2))
+    .sqrt()
+}
+
+/// CIE94 with the graphic arts weights (kL = 1, K1 = 0.045, K2 = 0.015).
+fn delta_e94(reference: Lab, sample: Lab) -> f32 {
+    let c1 = reference.a.hypot(reference.b);
+    let c2 = sample.a.hypot(sample.b);
+    let dl = reference.l - sample.l;
+    let dc = c1 - c2;
+    let dh_squared =
+        ((reference.a - sample.a).powi(2) + (reference.b - sample.b).powi(2) - dc * dc).max(0.0);
+    let sc = 1.0 + 0.045 * c1;
+    let sh = 1.0 + 0.015 * c1;
+    (dl * dl + (dc / sc).powi(2) + dh_squared / (sh * sh)).sqrt()
+}
+
+/// CIEDE2000 (Sharma, Wu and Dalal 2005) with unit weighting factors.
+fn delta_e2000(reference: Lab, sample: Lab) -> f32 {
+    let (l1, a1, b1) = (reference.l as f64, reference.a as f64, reference.b as f64);
+    let (l2, a2, b2) = (sample.l as f64, sample.a as f64, sample.b as f64);
+    let pow25_7 = 25f64.powi(7);
+
+    let c_mean7 = ((a1.hypot(b1) + a2.hypot(b2)) / 2.0).powi(7);
+    let g = 0.5 * (1.0 - (c_mean7 / (c_mean7 + pow25_7)).sqrt());
+    let (a1p, a2p) = (a1 * (1.0 + g), a2 * (1.0 + g));
+    let (c1p, c2p) = (a1p.hypot(b1), a2p.hypot(b2));
+    let hue = |b: f64, a: f64| {
+        if a == 0.0 && b == 0.0 {
+            0.0
+        } else {
+            b.atan2(a).to_degrees().rem_euclid(360.0)
+        }
+    };
+    let (h1p, h2p) = (hue(b1, a1p), hue(b2, a2p));
+    let chromatic = c1p * c2p != 0.0;
+
+    let dlp = l2 - l1;
+    let dcp = c2p - c1p;
+    let dhp = match h2p - h1p {
+        _ if !chromatic => 0.0,
+        diff if diff > 180.0 => diff - 360.0,
+        diff if diff < -180.0 => diff + 360.0,
+        diff => diff,
+    };
+    let dhp_big = 2.0 * (c1p * c2p).sqrt() * (dhp.to_radians() / 2.0).sin();
+
+    let l_mean = (l1 + l2) / 2.0;
+    let cp_mean = (c1p + c2p) / 2.0;
+    let hp_mean = if !chromatic {
+        h1p + h2p
+    } else if (h1p - h2p).abs() <= 180.0 {
+        (h1p + h2p) / 2.0
+    } else if h1p + h2p < 360.0 {
+        (h1p + h2p + 360.0) / 2.0
+    } else {
+        (h1p + h2p - 360.0) / 2.0
+    };
+
+    let t = 1.0 - 0.17 * (hp_mean - 30.0).to_radians().cos()
+        + 0.24 * (2.0 * hp_mean).to_radians().cos()
+        + 0.32 * (3.0 * hp_mean + 6.0).to_radians().cos()
+        - 0.20 * (4.0 * hp_mean - 63.0).to_radians().cos();
+    let d_theta = 30.0 * (-((hp_mean - 275.0) / 25.0).powi(2)).exp();
+    let cp_mean7 = cp_mean.powi(7);
+    let rc = 2.0 * (cp_mean7 / (cp_mean7 + pow25_7)).sqrt();
+    let sl = 1.0 + 0.015 * (l_mean - 50.0).powi(2) / (20.0 + (l_mean - 50.0).powi(2)).sqrt();
+    let sc = 1.0 + 0.045 * cp_mean;
+    let sh = 1.0 + 0.015 * cp_mean * t;
+    let rt = -(2.0 * d_theta).to_radians().sin() * rc;
+
+    let (dl, dc, dh) = (dlp / sl, dcp / sc, dhp_big / sh);
+    (dl * dl + dc * dc + dh * dh + rt * dc * dh).sqrt() as f32
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CompareEntry {
+    timestamp: u64,
+    reference: (u8, u8, u8),
+    sample: (u8, u8, u8),
+    delta_e76: f32,
+    delta_e94: f32,
+    delta_e2000: f32,
+    tolerance: f32,
+}
+
+impl CompareEntry {
+    fn new(reference: (u8, u8, u8), sample: (u8, u8, u8), tolerance: f32) -> Self {
+        let (reference_lab, sample_lab) = (rgb_to_lab(reference), rgb_to_lab(sample));
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs())
+                .unwrap_or(0),
+            reference,
+            sample,
+            delta_e76: delta_e76(reference_lab, sample_lab),
+            delta_e94: delta_e94(reference_lab, sample_lab),
+            delta_e2000: delta_e2000(reference_lab, sample_lab),
+            tolerance,
+        }
+    }
 
-fn parse_percentage_0_to_1(value: &str) -> Option<f32> {
-    let trimmed = value.trim();
-    let raw = trimmed.strip_suffix('%').unwrap_or(trimmed).trim();
-    let parsed = raw.parse::<f32>().ok()?;
-    Some((parsed / 100.0).clamp(0.0, 1.0))
+    fn passes(&self) -> bool {
+        self.delta_e2000 <= self.tolerance
+    }
 }
 
-fn parse_hsl_permissive(value: &str) -> Option<(u8, u8, u8)> {
-    let payload = inner_function_payload(value, "hsl");
-    let parts: Vec<&str> = payload.split(',').map(str::trim).collect();
-    if parts.len() != 3 {
+fn current_compare_entry(ui: &AppWindow) -> Option<CompareEntry> {
+    if !ui.get_compare_reference_set() {
         return None;
     }
-
-    let h = parts[0].parse::<f32>().ok()?.rem_euclid(360.0);
-    let s = parse_percentage_0_to_1(parts[1])?;
-    let l = parse_percentage_0_to_1(parts[2])?;
-
-    let hsl = Hsl::new(h, s, l);
-    let rgb: Srgb = hsl.into_color();
-
-    Some((
-        (rgb.red.clamp(0.0, 1.0) * 255.0).round() as u8,
-        (rgb.green.clamp(0.0, 1.0) * 255.0).round() as u8,
-        (rgb.blue.clamp(0.0, 1.0) * 255.0).round() as u8,
+    let reference = ui.get_compare_reference();
+    Some(CompareEntry::new(
+        (reference.red(), reference.green(), reference.blue()),
+        srgb_to_rgb8(current_color()),
+        ui.get_compare_tolerance(),
     ))
 }
 
-fn parse_hsv_permissive(value: &str) -> Option<(u8, u8, u8)> {
-    let payload = inner_function_payload(value, "hsv");
-    let parts: Vec<&str> = payload.split(',').map(str::trim).collect();
-    if parts.len() != 3 {
-        return None;
-    }
+/// Refreshes the compare summary for the current color against the reference.
+fn update_compare(ui: &AppWindow) {
+    let Some(entry) = current_compare_entry(ui) else {
+        ui.set_compare_summary(tr("Pick or enter a reference color").into());
+        ui.set_compare_details("".into());
+        return;
+    };
 
-    let h = parts[0].parse::<f32>().ok()?.rem_euclid(360.0);
-    let s = parse_percentage_0_to_1(parts[1])?;
-    let v = parse_percentage_0_to_1(parts[2])?;
+    let verdict = if entry.passes() {
+        tr("Match")
+    } else {
+        tr("Mismatch")
+    };
+    ui.set_compare_pass(entry.passes());
+    ui.set_compare_summary(
+        format!(
+            "ΔE76 {}   ΔE94 {}   ΔE2000 {}   {verdict} (≤ {})",
+            localize_decimal(format!("{:.2}", entry.delta_e76)),
+            localize_decimal(format!("{:.2}", entry.delta_e94)),
+            localize_decimal(format!("{:.2}", entry.delta_e2000)),
+            localize_decimal(entry.tolerance.to_string()),
+        )
+        .into(),
+    );
 
-    let hsv = Hsv::new(h, s, v);
-    let rgb: Srgb = hsv.into_color();
+    let (reference_lab, sample_lab) = (rgb_to_lab(entry.reference), rgb_to_lab(entry.sample));
+    let channel = |reference: u8, sample: u8| sample as i16 - reference as i16;
+    ui.set_compare_details(
+        format!(
+            "ΔL* {}  Δa* {}  Δb* {}   ΔR {:+}  ΔG {:+}  ΔB {:+}",
+            localize_decimal(format!("{:+.2}", sample_lab.l - reference_lab.l)),
+            localize_decimal(format!("{:+.2}", sample_lab.a - reference_lab.a)),
+            localize_decimal(format!("{:+.2}", sample_lab.b - reference_lab.b)),
+            channel(entry.reference.0, entry.sample.0),
+            channel(entry.reference.1, entry.sample.1),
+            channel(entry.reference.2, entry.sample.2),
+        )
+        .into(),
+    );
+}
 
-    Some((
-        (rgb.red.clamp(0.0, 1.0) * 255.0).round() as u8,
-        (rgb.green.clamp(0.0, 1.0) * 255.0).round() as u8,
-        (rgb.blue.clamp(0.0, 1.0) * 255.0).round() as u8,
-    ))
+fn set_compare_reference(ui: &AppWindow, rgb: (u8, u8, u8)) {
+    let (r, g, b) = rgb;
+    ui.set_compare_reference(Color::from_rgb_u8(r, g, b));
+    ui.set_compare_reference_text(format_hex(r, g, b).into());
+    ui.set_compare_reference_set(true);
+    update_compare(ui);
 }
 
-fn parse_color(field: ColorField, value: &str) -> Option<(u8, u8, u8)> {
-    match field {
-        ColorField::Hex => parse_hex_flexible(value),
-        ColorField::Rgb => parse_rgb_permissive(value),
-        ColorField::Hsl => parse_hsl_permissive(value),
-        ColorField::Hsv => parse_hsv_permissive(value),
+fn export_compare_csv(entries: &[CompareEntry]) -> Result<PathBuf, String> {
+    let path = csv_export_path("compare")?;
+
+    let mut csv = String::from(
+        "timestamp,reference,sample,delta_e76,delta_e94,delta_e2000,tolerance,verdict\n",
+    );
+    for entry in entries {
+        let (reference, sample) = (entry.reference, entry.sample);
+        csv.push_str(&format!(
+            "{},{},{},{:.4},{:.4},{:.4},{},{}\n",
+            entry.timestamp,
+            format_hex(reference.0, reference.1, reference.2),
+            format_hex(sample.0, sample.1, sample.2),
+            entry.delta_e76,
+            entry.delta_e94,
+            entry.delta_e2000,
+            entry.tolerance,
+            if entry.passes() { "match" } else { "mismatch" }
+        ));
     }
-}
 
-fn sync_history_model(ui: &AppWindow, history_store: &Arc<Mutex<Vec<(u8, u8, u8)>>>) {
-    let colors: Vec<Color> = {
-        let guard = history_store.lock().unwrap();
-        guard
-            .iter()
-            .map(|(r, g, b)| Color::from_rgb_u8(*r, *g, *b))
-            .collect()
-    };
-    ui.set_history_model(ModelRc::from(Rc::new(VecModel::from(colors))));
+    fs::write(&path, csv).map_err(|err| format!("{}: {err}", path.display()))?;
+    Ok(path)
 }
 
-fn push_history(history_store: &Arc<Mutex<Vec<(u8, u8, u8)>>>, rgb: (u8, u8, u8)) {
-    let mut guard = history_store.lock().unwrap();
-    guard.insert(0, rgb);
+/// Notification `image-data` hint (`iiibiiay`) filled with a solid swatch of `rgb`.
+fn notification_swatch(rgb: (u8, u8, u8)) -> Value<'static> {
+    let (r, g, b) = rgb;
+    let size = NOTIFY_SWATCH_SIZE;
+    let data: Vec<u8> = [r, g, b].repeat((size * size) as usize);
+    Value::from(Structure::from((
+        size,
+        size,
+        size * 3,
+        false,
+        8i32,
+        3i32,
+        data,
+    )))
 }
 
-fn copy_text_async(text: String) {
-    thread::spawn(move || match Clipboard::new() {
-        Ok(mut clipboard) => {
-            let _ = clipboard.set().wait().text(text);
+/// Tells the user what a stealth pick copied, with action buttons handled until the
+/// notification closes.
+fn notify_color_copied(
+    ui_weak: slint::Weak<AppWindow>,
+    history_store: HistoryStore,
+    rgb: (u8, u8, u8),
+    copied: String,
+) {
+    thread::spawn(move || {
+        if let Err(err) = run_copy_notification(&ui_weak, &history_store, rgb, &copied) {
+            eprintln!("notify: {err}");
         }
-        Err(err) => eprintln!("Clipboard error: {err}"),
     });
 }
 
-fn apply_selected_color(
-    ui: &AppWindow,
-    history_store: &Arc<Mutex<Vec<(u8, u8, u8)>>>,
-    r: u8,
-    g: u8,
-    b: u8,
-) {
-    push_history(history_store, (r, g, b));
-    sync_history_model(ui, history_store);
-    update_ui_colors(ui, r, g, b);
-
-    if ui.get_setting_autocopy() {
-        copy_text_async(format_hex(r, g, b));
+fn run_copy_notification(
+    ui_weak: &slint::Weak<AppWindow>,
+    history_store: &HistoryStore,
+    rgb: (u8, u8, u8),
+    copied: &str,
+) -> Result<(), String> {
+    let connection =
+        ZbusConnection::session().map_err(|err| format!("session bus failed: {err}"))?;
+    let proxy = ZbusProxy::new(
+        &connection,
+        "org.freedesktop.Notifications",
+        "/org/freedesktop/Notifications",
+        "org.freedesktop.Notifications",
+    )
+    .map_err(|err| format!("notifications proxy failed: {err}"))?;
+
+    // Subscribe before sending so a quick click cannot slip past us.
+    let signals = proxy
+        .receive_all_signals()
+        .map_err(|err| format!("signal stream failed: {err}"))?;
+
+    let labels = [tr("Open Details"), tr("Copy as RGB"), tr("Save to Palette")];
+    let actions = [
+        NOTIFY_ACTION_OPEN,
+        labels[0].as_str(),
+        NOTIFY_ACTION_COPY_RGB,
+        labels[1].as_str(),
+        NOTIFY_ACTION_SAVE_PALETTE,
+        labels[2].as_str(),
+    ];
+    let mut hints: HashMap<&str, Value<'_>> = HashMap::new();
+    hints.insert("image-data", notification_swatch(rgb));
+    hints.insert("transient", Value::from(true));
+
+    // The description helps when the swatch alone says little, unless it was the copy itself.
+    let description = describe_color(srgb_from_rgb8(rgb));
+    let body = if copied == description {
+        description
     } else {
-        ui.window().show().ok();
-    }
+        format!("{copied}\n{description}")
+    };
 
-    persist_config(ui, history_store);
-}
+    let reply = proxy
+        .call_method(
+            "Notify",
+            &(
+                "Archtoys",
+                0u32,
+                "archtoys",
+                tr("Color copied").as_str(),
+                body.as_str(),
+                &actions[..],
+                &hints,
+                -1i32,
+            ),
+        )
+        .map_err(|err| format!("Notify call failed: {err}"))?;
+    let notification_id: u32 = reply
+        .body()
+        .deserialize()
+        .map_err(|err| format!("Notify reply decode failed: {err}"))?;
 
-fn overlay_position(x: i32, y: i32, screen_w: i32, screen_h: i32) -> (i32, i32) {
-    let mut pos_x = x + OVERLAY_OFFSET_X;
-    let mut pos_y = y + OVERLAY_OFFSET_Y;
+    for message in signals {
+        let header = message.header();
+        let Some(member) = header.member() else {
+            continue;
+        };
+        match member.as_str() {
+            "ActionInvoked" => {
+                let Ok((id, action)) = message.body().deserialize::<(u32, String)>() else {
+                    continue;
+                };
+                if id != notification_id {
+                    continue;
+                }
+                let history_store = history_store.clone();
+                let _ = ui_weak.upgrade_in_event_loop(move |ui| {
+                    let (r, g, b) = rgb;
+                    match action.as_str() {
+                        NOTIFY_ACTION_OPEN => {
+                            update_ui_colors(&ui, r, g, b);
+                            ui.window().show().ok();
+                        }
+                        NOTIFY_ACTION_COPY_RGB => {
+                            copy_color_async(format_canonical(ColorField::Rgb, rgb), rgb);
+                        }
+                        NOTIFY_ACTION_SAVE_PALETTE => add_to_palette(&ui, &history_store, rgb),
+                        _ => {}
+                    }
+                });
+            }
+            "NotificationClosed" => {
+                let Ok((id, _reason)) = message.body().deserialize::<(u32, u32)>() else {
+                    continue;
+                };
+                if id == notification_id {
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
 
-    if pos_x + OVERLAY_WIDTH > screen_w {
-        pos_x = x - OVERLAY_WIDTH - OVERLAY_OFFSET_X;
+/// Places an overlay of `size` beside `cursor`, flipping it to the other side near the edges
+/// of `monitor` (`x, y, width, height`). Everything is in physical pixels.
+fn overlay_position(
+    (x, y): (i32, i32),
+    (width, height): (i32, i32),
+    (offset_x, offset_y): (i32, i32),
+    (monitor_x, monitor_y, monitor_w, monitor_h): (i32, i32, i32, i32),
+) -> (i32, i32) {
+    let mut pos_x = x + offset_x;
+    let mut pos_y = y + offset_y;
+
+    if pos_x + width > monitor_x + monitor_w {
+        pos_x = x - width - offset_x;
     }
-    if pos_y + OVERLAY_HEIGHT > screen_h {
-        pos_y = y - OVERLAY_HEIGHT - OVERLAY_OFFSET_Y;
+    if pos_y + height > monitor_y + monitor_h {
+        pos_y = y - height - offset_y;
     }
 
-    let max_x = (screen_w - OVERLAY_WIDTH).max(0);
-    let max_y = (screen_h - OVERLAY_HEIGHT).max(0);
-    pos_x = pos_x.clamp(0, max_x);
-    pos_y = pos_y.clamp(0, max_y);
+    let max_x = (monitor_x + monitor_w - width).max(monitor_x);
+    let max_y = (monitor_y + monitor_h - height).max(monitor_y);
+    pos_x = pos_x.clamp(monitor_x, max_x);
+    pos_y = pos_y.clamp(monitor_y, max_y);
 
     (pos_x, pos_y)
 }
 
-fn finish_picker(ui_weak: slint::Weak<AppWindow>, context: PickerContext, selected: bool) {
+/// Physical `(x, y, width, height)` of every active RandR monitor.
+fn x11_monitors() -> Result<Vec<(i32, i32, i32, i32)>, String> {
+    let (conn, screen_num) =
+        x11rb::connect(None).map_err(|err| format!("x11 connect failed: {err}"))?;
+    let root = conn.setup().roots[screen_num].root;
+    let reply = conn
+        .randr_get_monitors(root, true)
+        .map_err(|err| format!("get_monitors failed: {err}"))?
+        .reply()
+        .map_err(|err| format!("get_monitors reply failed: {err}"))?;
+    Ok(reply
+        .monitors
+        .iter()
+        .map(|monitor| {
+            (
+                i32::from(monitor.x),
+                i32::from(monitor.y),
+                i32::from(monitor.width),
+                i32::from(monitor.height),
+            )
+        })
+        .collect())
+}
+
+/// Scale factor of the RandR monitor `(x, y, width, height)`, as winit computes it.
+fn monitor_scale_factor(window: &slint::Window, (x, y, _, _): (i32, i32, i32, i32)) -> Option<f32> {
+    use slint::winit_030::WinitWindowAccessor;
+
+    window
+        .with_winit_window(|window| {
+            window
+                .available_monitors()
+                .find(|handle| {
+                    let position = handle.position();
+                    position.x == x && position.y == y
+                })
+                .map(|handle| handle.scale_factor() as f32)
+        })
+        .flatten()
+}
+
+/// The `pixels`×`pixels` square centered on `(x, y)`, row by row; pixels off the frame
+/// are black.
+fn frame_loupe(
+    frame: &[u8],
+    width: i32,
+    height: i32,
+    (x, y): (i32, i32),
+    pixels: i32,
+) -> Vec<(u8, u8, u8)> {
+    let radius = pixels / 2;
+    (-radius..=radius)
+        .flat_map(|dy| (-radius..=radius).map(move |dx| (x + dx, y + dy)))
+        .map(|(px, py)| {
+            let inside = (0..width).contains(&px) && (0..height).contains(&py);
+            inside
+                .then(|| frame_pixel(frame, width, height, px, py))
+                .flatten()
+                .unwrap_or((0, 0, 0))
+        })
+        .collect()
+}
+
+/// Side of the loupe square: `overlay_loupe_pixels`, kept odd so one pixel sits centered.
+fn loupe_pixels(cfg: &AppConfig) -> u32 {
+    cfg.overlay_loupe_pixels.clamp(3, 31) | 1
+}
+
+fn loupe_image(colors: &[(u8, u8, u8)], pixels: u32) -> slint::Image {
+    let mut buffer = SharedPixelBuffer::<Rgba8Pixel>::new(pixels, pixels);
+    for (pixel, &(r, g, b)) in buffer.make_mut_slice().iter_mut().zip(colors) {
+        *pixel = Rgba8Pixel::new(r, g, b, 255);
+    }
+    slint::Image::from_rgba8(buffer)
+}
+
+fn finish_picker(
+    ui_weak: slint::Weak<AppWindow>,
+    history_store: &HistoryStore,
+    context: PickerContext,
+    selected: bool,
+) {
+    let history_store = history_store.clone();
     if let Err(err) = slint::invoke_from_event_loop(move || {
         release_picker_overlay();
         release_picker_shield();
 
         if let Some(ui) = ui_weak.upgrade() {
+            let copy_field = context.outcome.copy_field(&ui);
             let stealth = selected
-                && context.source == PickerSource::Hotkey
+                && context.source != PickerSource::Button
                 && !context.was_visible_before_trigger
-                && ui.get_setting_autocopy();
+                && copy_field.is_some();
 
             if ui.get_setting_minimize() && !stealth {
                 ui.window().show().ok();
             }
+
+            if let Some(field) = copy_field.filter(|_| stealth && ui.get_setting_notify()) {
+                let current = current_color_wide();
+                notify_color_copied(
+                    ui.as_weak(),
+                    history_store,
+                    srgb_to_rgb8(fit_srgb(current)),
+                    format_color(field, current),
+                );
+            }
         }
 
         PICKER_ACTIVE.store(false, Ordering::SeqCst);
@@ -876,7 +4810,7 @@ fn finish_picker(ui_weak: slint::Weak<AppWindow>, context: PickerContext, select
 
 fn start_x11_picker(
     ui_weak: slint::Weak<AppWindow>,
-    history_store: Arc<Mutex<Vec<(u8, u8, u8)>>>,
+    history_store: HistoryStore,
     context: PickerContext,
 ) {
     let overlay_weak = match ensure_picker_overlay() {
@@ -900,6 +4834,15 @@ fn start_x11_picker(
         shield.show().ok();
     }
 
+    let cfg = synced_config();
+    let loupe_pixels = loupe_pixels(&cfg);
+    if let Some(overlay) = overlay_weak.upgrade() {
+        overlay.set_requested_width(cfg.overlay_width as f32);
+        overlay.set_requested_height(cfg.overlay_height as f32);
+        overlay.set_show_loupe(cfg.overlay_loupe);
+        overlay.set_loupe_pixels(loupe_pixels as i32);
+    }
+
     thread::spawn(move || {
         let _pointer_grab = match X11PointerGrab::acquire() {
             Ok(guard) => Some(guard),
@@ -915,7 +4858,7 @@ fn start_x11_picker(
             Ok(display) => display,
             Err(err) => {
                 eprintln!("x11 picker: could not get primary display: {err:?}");
-                finish_picker(ui_weak, context, false);
+                finish_picker(ui_weak, &history_store, context, false);
                 return;
             }
         };
@@ -924,14 +4867,36 @@ fn start_x11_picker(
             Ok(capturer) => capturer,
             Err(err) => {
                 eprintln!("x11 picker: could not create capturer: {err:?}");
-                finish_picker(ui_weak, context, false);
+                finish_picker(ui_weak, &history_store, context, false);
                 return;
             }
         };
 
+        let color_transform = DisplayColorTransform::new(context.color_space);
+        let convert = |rgb: (u8, u8, u8)| match &color_transform {
+            Some(transform) => transform.apply(rgb),
+            None => srgb_from_rgb8(rgb),
+        };
+        let convert_rgb8 = |rgb: (u8, u8, u8)| match &color_transform {
+            Some(transform) => transform.apply_rgb8(rgb),
+            None => rgb,
+        };
+        let color_note = color_transform
+            .as_ref()
+            .map(|transform| transform.note.clone())
+            .unwrap_or_default();
+
+        // Without RandR the whole captured screen counts as one monitor.
+        let monitors = x11_monitors().unwrap_or_else(|err| {
+            eprintln!("x11 picker: {err}");
+            Vec::new()
+        });
+
         let mut prev_left_pressed = false;
+        let mut region_anchor: Option<(i32, i32)> = None;
         let mut last_color: (u8, u8, u8) = (0, 0, 0);
-        let mut last_hex = String::from("000000");
+        let mut last_wide = Srgb::new(0.0, 0.0, 0.0);
+        let mut last_loupe: Vec<(u8, u8, u8)> = Vec::new();
         let mut selected = false;
 
         loop {
@@ -949,19 +4914,20 @@ fn start_x11_picker(
 
             match capturer.frame() {
                 Ok(frame) => {
-                    if width > 0 && height > 0 {
-                        let safe_x = mouse_x.clamp(0, width.saturating_sub(1));
-                        let safe_y = mouse_y.clamp(0, height.saturating_sub(1));
-                        let stride = width as usize * 4;
-                        let idx = safe_y as usize * stride + safe_x as usize * 4;
-                        if idx + 2 < frame.len() {
-                            let b = frame[idx];
-                            let g = frame[idx + 1];
-                            let r = frame[idx + 2];
-                            last_color = (r, g, b);
-                            last_hex = format!("{:02X}{:02X}{:02X}", r, g, b);
-                            updated = true;
-                        }
+                    if let Some(color) =
+                        frame_pixel(&frame, width, height, mouse_x, mouse_y).map(convert)
+                    {
+                        last_wide = color;
+                        last_color = srgb_to_rgb8(fit_srgb(color));
+                        updated = true;
+                    }
+                    if cfg.overlay_loupe {
+                        let cursor = (mouse_x, mouse_y);
+                        last_loupe =
+                            frame_loupe(&frame, width, height, cursor, loupe_pixels as i32)
+                                .into_iter()
+                                .map(convert_rgb8)
+                                .collect();
                     }
                 }
                 Err(err) => {
@@ -972,9 +4938,43 @@ fn start_x11_picker(
                 }
             }
 
-            let (pos_x, pos_y) = overlay_position(mouse_x, mouse_y, width, height);
+            let monitor = monitors
+                .iter()
+                .copied()
+                .find(|&(x, y, w, h)| {
+                    (x..x + w).contains(&mouse_x) && (y..y + h).contains(&mouse_y)
+                })
+                .unwrap_or((0, 0, width, height));
             let (r, g, b) = last_color;
-            let overlay_hex = format!("#{}", last_hex);
+            let picked = last_wide;
+            let instruction = match (context.outcome, region_anchor) {
+                (PickOutcome::Palette, None) => Some(tr("Corner 1")),
+                (PickOutcome::Palette, Some(_)) => Some(tr("Corner 2")),
+                (PickOutcome::Gradient, None) => Some(tr("Line start")),
+                (PickOutcome::Gradient, Some(_)) => Some(tr("Line end")),
+                _ => None,
+            };
+            let overlay_lines: Vec<SharedString> = instruction
+                .into_iter()
+                .chain(
+                    cfg.overlay_formats
+                        .iter()
+                        .map(|field| format_color(*field, picked)),
+                )
+                .map(SharedString::from)
+                .collect();
+            let overlay_name = if cfg.overlay_name {
+                nearest_color_name(last_color).to_string()
+            } else {
+                String::new()
+            };
+            let overlay_coordinates = if cfg.overlay_coordinates {
+                format!("x {mouse_x}, y {mouse_y}")
+            } else {
+                String::new()
+            };
+            let overlay_loupe = last_loupe.clone();
+            let offset = (cfg.overlay_offset_x, cfg.overlay_offset_y);
 
             let ui_weak2 = ui_weak.clone();
             let overlay_weak2 = overlay_weak.clone();
@@ -994,17 +4994,43 @@ fn start_x11_picker(
 
                 if updated {
                     if let Some(ui) = ui_weak2.upgrade() {
-                        update_preview_color(&ui, r, g, b);
-                        ui.set_val_hex(overlay_hex.clone().into());
+                        update_preview_color(&ui, picked);
+                        ui.set_val_hex(format_hex(r, g, b).into());
                     }
                 }
 
                 if let Some(overlay) = overlay_weak2.upgrade() {
                     overlay.set_preview_color(Color::from_rgb_u8(r, g, b));
-                    overlay.set_preview_hex(overlay_hex.into());
-                    let scale = overlay.window().scale_factor();
-                    let logical = LogicalPosition::new(pos_x as f32 / scale, pos_y as f32 / scale);
-                    overlay.window().set_position(logical);
+                    overlay.set_preview_lines(ModelRc::new(VecModel::from(overlay_lines)));
+                    overlay.set_preview_name(overlay_name.into());
+                    overlay.set_preview_coordinates(overlay_coordinates.into());
+                    if !overlay_loupe.is_empty() {
+                        overlay.set_loupe(loupe_image(&overlay_loupe, loupe_pixels));
+                    }
+
+                    // The cursor and monitor are physical. Offset and size scale with the
+                    // monitor under the cursor, which the overlay may not have reached yet.
+                    let current_scale = overlay.window().scale_factor();
+                    let scale =
+                        monitor_scale_factor(overlay.window(), monitor).unwrap_or(current_scale);
+                    let size = overlay
+                        .window()
+                        .size()
+                        .to_logical(current_scale)
+                        .to_physical(scale);
+                    let offset = (
+                        (offset.0 as f32 * scale).round() as i32,
+                        (offset.1 as f32 * scale).round() as i32,
+                    );
+                    let (pos_x, pos_y) = overlay_position(
+                        (mouse_x, mouse_y),
+                        (size.width as i32, size.height as i32),
+                        offset,
+                        monitor,
+                    );
+                    overlay
+                        .window()
+                        .set_position(PhysicalPosition::new(pos_x, pos_y));
                     overlay.show().ok();
                 }
             });
@@ -1013,17 +5039,85 @@ fn start_x11_picker(
             }
 
             let left_pressed = mouse.button_pressed.get(1).copied().unwrap_or(false);
-            if left_pressed && !prev_left_pressed {
+            let left_released = prev_left_pressed && !left_pressed;
+            if context.outcome == PickOutcome::Gradient {
+                let end = (mouse_x, mouse_y);
+                let dragged = |anchor: (i32, i32)| {
+                    let (dx, dy) = (end.0 - anchor.0, end.1 - anchor.1);
+                    dx * dx + dy * dy >= 16
+                };
+                match region_anchor {
+                    None if left_pressed && !prev_left_pressed => region_anchor = Some(end),
+                    // A drag ends on release; a plain click waits for a second click instead.
+                    Some(anchor)
+                        if (left_pressed && !prev_left_pressed)
+                            || (left_released && dragged(anchor)) =>
+                    {
+                        let colors: Vec<_> = capture_line_samples(&mut capturer, anchor, end)
+                            .into_iter()
+                            .map(convert_rgb8)
+                            .collect();
+                        let angle = css_gradient_angle(anchor, end);
+                        selected = !colors.is_empty();
+                        let ui_weak2 = ui_weak.clone();
+                        let _ = slint::invoke_from_event_loop(move || {
+                            if let Some(ui) = ui_weak2.upgrade() {
+                                apply_gradient_samples(&ui, &colors, angle);
+                            }
+                        });
+                        break;
+                    }
+                    _ => {}
+                }
+            } else if left_pressed && !prev_left_pressed {
                 let hs = history_store.clone();
                 let ui_weak2 = ui_weak.clone();
-                let _ = slint::invoke_from_event_loop(move || {
-                    if let Some(ui) = ui_weak2.upgrade() {
-                        apply_selected_color(&ui, &hs, r, g, b);
+
+                if context.outcome == PickOutcome::Palette {
+                    match region_anchor {
+                        None => region_anchor = Some((mouse_x, mouse_y)),
+                        Some(anchor) => {
+                            let colors: Vec<_> =
+                                capture_region_palette(&mut capturer, anchor, (mouse_x, mouse_y))
+                                    .into_iter()
+                                    .map(convert_rgb8)
+                                    .collect();
+                            selected = !colors.is_empty();
+                            let note = color_note.clone();
+                            let _ = slint::invoke_from_event_loop(move || {
+                                if let Some(ui) = ui_weak2.upgrade() {
+                                    apply_region_palette(&ui, &hs, &colors);
+                                    ui.set_color_space_note(note.into());
+                                }
+                            });
+                            break;
+                        }
                     }
-                });
+                } else if context.outcome == PickOutcome::Watch {
+                    let point = (mouse_x, mouse_y);
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(ui) = ui_weak2.upgrade() {
+                            start_point_watch(&ui, point);
+                        }
+                    });
 
-                selected = true;
-                break;
+                    selected = true;
+                    break;
+                } else {
+                    let outcome = context.outcome;
+                    let note = color_note.clone();
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(ui) = ui_weak2.upgrade() {
+                            apply_selected_color(&ui, &hs, outcome, picked);
+                            if outcome != PickOutcome::Reference {
+                                ui.set_color_space_note(note.into());
+                            }
+                        }
+                    });
+
+                    selected = true;
+                    break;
+                }
             }
             prev_left_pressed = left_pressed;
 
@@ -1036,7 +5130,7 @@ fn start_x11_picker(
             thread::sleep(Duration::from_millis(16));
         }
 
-        finish_picker(ui_weak, context, selected);
+        finish_picker(ui_weak, &history_store, context, selected);
     });
 }
 
@@ -1074,6 +5168,330 @@ fn wait_for_portal_response(
         .map_err(|err| format!("portal: response decode failed: {err}"))
 }
 
+/// Global shortcuts registered through `org.freedesktop.portal.GlobalShortcuts`.
+///
+/// Wayland compositors do not deliver X11 key grabs to native windows, so this replaces
+/// `GlobalHotKeyManager` there.
+#[derive(Clone)]
+struct PortalShortcuts {
+    connection: ZbusConnection,
+    session: OwnedObjectPath,
+}
+
+impl PortalShortcuts {
+    fn proxy(connection: &ZbusConnection) -> Result<ZbusProxy<'static>, String> {
+        ZbusProxy::new(
+            connection,
+            "org.freedesktop.portal.Desktop",
+            "/org/freedesktop/portal/desktop",
+            "org.freedesktop.portal.GlobalShortcuts",
+        )
+        .map_err(|err| format!("portal: global shortcuts proxy failed: {err}"))
+    }
+
+    fn create_session() -> Result<Self, String> {
+        let connection = ZbusConnection::session()
+            .map_err(|err| format!("portal: session bus failed: {err}"))?;
+        let proxy = Self::proxy(&connection)?;
+
+        let handle_token = next_portal_handle_token("archtoys_shortcuts");
+        let session_token = next_portal_handle_token("archtoys_session");
+        let mut options: HashMap<&str, Value<'_>> = HashMap::new();
+        options.insert("handle_token", Value::from(handle_token.as_str()));
+        options.insert("session_handle_token", Value::from(session_token.as_str()));
+
+        let reply = proxy
+            .call_method("CreateSession", &(&options,))
+            .map_err(|err| format!("portal: CreateSession call failed: {err}"))?;
+        let (handle_path,): (OwnedObjectPath,) = reply
+            .body()
+            .deserialize()
+            .map_err(|err| format!("portal: CreateSession reply decode failed: {err}"))?;
+
+        let (response_code, results) = wait_for_portal_response(&connection, &handle_path)?;
+        if response_code != 0 {
+            return Err(format!(
+                "portal: CreateSession rejected with code {response_code}"
+            ));
+        }
+
+        let session_value = results
+            .get("session_handle")
+            .ok_or_else(|| "portal: response did not include session_handle".to_string())?;
+        let session_text = session_value
+            .downcast_ref::<&str>()
+            .map(str::to_string)
+            .or_else(|_| {
+                session_value
+                    .downcast_ref::<&ObjectPath>()
+                    .map(|path| path.as_str().to_string())
+            })
+            .map_err(|_| "portal: session_handle type conversion failed".to_string())?;
+        let session = OwnedObjectPath::try_from(session_text)
+            .map_err(|err| format!("portal: invalid session handle: {err}"))?;
+
+        Ok(Self {
+            connection,
+            session,
+        })
+    }
+
+    /// Binds `(id, description, preferred trigger)` shortcuts and returns the trigger
+    /// descriptions the compositor assigned, keyed by shortcut id.
+    fn bind(
+        &self,
+        shortcuts: &[(String, String, String)],
+    ) -> Result<HashMap<String, String>, String> {
+        let proxy = Self::proxy(&self.connection)?;
+
+        let entries: Vec<(&str, HashMap<&str, Value<'_>>)> = shortcuts
+            .iter()
+            .map(|(id, description, trigger)| {
+                let mut props: HashMap<&str, Value<'_>> = HashMap::new();
+                props.insert("description", Value::from(description.as_str()));
+                props.insert("preferred_trigger", Value::from(trigger.as_str()));
+                (id.as_str(), props)
+            })
+            .collect();
+
+        let handle_token = next_portal_handle_token("archtoys_bind");
+        let mut options: HashMap<&str, Value<'_>> = HashMap::new();
+        options.insert("handle_token", Value::from(handle_token.as_str()));
+
+        let reply = proxy
+            .call_method("BindShortcuts", &(&self.session, &entries, "", &options))
+            .map_err(|err| format!("portal: BindShortcuts call failed: {err}"))?;
+        let (handle_path,): (OwnedObjectPath,) = reply
+            .body()
+            .deserialize()
+            .map_err(|err| format!("portal: BindShortcuts reply decode failed: {err}"))?;
+
+        let (response_code, results) = wait_for_portal_response(&self.connection, &handle_path)?;
+        if response_code != 0 {
+            return Err(format!(
+                "portal: BindShortcuts rejected with code {response_code}"
+            ));
+        }
+
+        Ok(results
+            .get("shortcuts")
+            .map(portal_shortcut_triggers)
+            .unwrap_or_default())
+    }
+
+    /// Blocks on the `Activated` signal and reports the ids of triggered shortcuts.
+    fn listen(&self, mut on_activated: impl FnMut(&str)) -> Result<(), String> {
+        let proxy = Self::proxy(&self.connection)?;
+        let activations = proxy
+            .receive_signal("Activated")
+            .map_err(|err| format!("portal: activation stream failed: {err}"))?;
+
+        for message in activations {
+            let decoded = message
+                .body()
+                .deserialize::<(OwnedObjectPath, String, u64, HashMap<String, OwnedValue>)>();
+            match decoded {
+                Ok((session, shortcut_id, _timestamp, _options)) if session == self.session => {
+                    on_activated(&shortcut_id);
+                }
+                Ok(_) => {}
+                Err(err) => eprintln!("portal: activation decode failed: {err}"),
+            }
+        }
+
+        Err("portal: activation stream ended".to_string())
+    }
+}
+
+/// Reads `trigger_description` out of a BindShortcuts `a(sa{sv})` result.
+fn portal_shortcut_triggers(value: &OwnedValue) -> HashMap<String, String> {
+    let mut triggers = HashMap::new();
+    let Value::Array(entries) = &**value else {
+        return triggers;
+    };
+
+    for entry in entries.inner() {
+        let Value::Structure(entry) = entry else {
+            continue;
+        };
+        let [Value::Str(id), Value::Dict(props)] = entry.fields() else {
+            continue;
+        };
+        for (key, prop) in props.iter() {
+            if key.downcast_ref::<&str>().ok() != Some("trigger_description") {
+                continue;
+            }
+            if let Ok(trigger) = prop.downcast_ref::<&str>() {
+                triggers.insert(id.as_str().to_string(), trigger.to_string());
+            }
+        }
+    }
+    triggers
+}
+
+/// Desktop appearance value reported by the Settings portal or kdeglobals.
+#[derive(Debug, Clone, Copy)]
+enum SystemAppearance {
+    /// `color-scheme`: true when the desktop prefers dark.
+    Dark(bool),
+    /// `contrast`: true when the desktop asks for high contrast.
+    HighContrast(bool),
+    Accent((u8, u8, u8)),
+}
+
+/// Decodes an `org.freedesktop.appearance` value. `Read` wraps it in an extra variant,
+/// `ReadOne` and `SettingChanged` do not.
+fn appearance_from_setting(key: &str, value: &OwnedValue) -> Option<SystemAppearance> {
+    let value = match &**value {
+        Value::Value(inner) => &**inner,
+        other => other,
+    };
+    match (key, value) {
+        // 0 is "no preference", which we treat as our light default.
+        ("color-scheme", Value::U32(scheme)) => Some(SystemAppearance::Dark(*scheme == 1)),
+        ("contrast", Value::U32(contrast)) => Some(SystemAppearance::HighContrast(*contrast == 1)),
+        ("accent-color", Value::Structure(rgb)) => {
+            let [Value::F64(r), Value::F64(g), Value::F64(b)] = rgb.fields() else {
+                return None;
+            };
+            // Channels outside 0-1 mean the desktop has no accent color set.
+            if ![r, g, b]
+                .iter()
+                .all(|channel| (0.0..=1.0).contains(*channel))
+            {
+                return None;
+            }
+            let to_u8 = |channel: f64| (channel * 255.0).round() as u8;
+            Some(SystemAppearance::Accent((to_u8(*r), to_u8(*g), to_u8(*b))))
+        }
+        _ => None,
+    }
+}
+
+fn read_appearance_setting(proxy: &ZbusProxy, key: &str) -> Option<SystemAppearance> {
+    // ReadOne is newer; older portals only implement the deprecated Read.
+    let reply = proxy
+        .call_method("ReadOne", &(APPEARANCE_NAMESPACE, key))
+        .or_else(|_| proxy.call_method("Read", &(APPEARANCE_NAMESPACE, key)))
+        .ok()?;
+    let (value,): (OwnedValue,) = reply.body().deserialize().ok()?;
+    appearance_from_setting(key, &value)
+}
+
+/// KDE's accent color from `kdeglobals`: `[General] AccentColor`, or the color scheme's
+/// selection background when no accent is set.
+fn kde_accent_color() -> Option<(u8, u8, u8)> {
+    let data = fs::read_to_string(config_base_dir().join("kdeglobals")).ok()?;
+    let mut section = "";
+    let mut accent = None;
+    let mut selection = None;
+    for line in data.lines().map(str::trim) {
+        if let Some(name) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+        {
+            section = name;
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match (section, key.trim()) {
+            ("General", "AccentColor") => accent = detect_color(value),
+            ("Colors:Selection", "BackgroundNormal") => selection = detect_color(value),
+            _ => {}
+        }
+    }
+    accent.or(selection)
+}
+
+fn apply_system_appearance(ui_weak: &slint::Weak<AppWindow>, appearance: SystemAppearance) {
+    let _ = ui_weak.upgrade_in_event_loop(move |ui| match appearance {
+        SystemAppearance::Dark(dark) => {
+            SYSTEM_DARK.with(|slot| *slot.borrow_mut() = Some(dark));
+            if ui.get_setting_follow_system() && ui.global::<Skin>().get_dark_mode() != dark {
+                // settings-changed applies the scheme, saves it and updates the tray.
+                ui.invoke_settings_changed();
+            }
+        }
+        SystemAppearance::HighContrast(high) => {
+            SYSTEM_HIGH_CONTRAST.with(|slot| *slot.borrow_mut() = Some(high));
+            ui.set_system_contrast_known(true);
+            if ui.get_setting_follow_system() && ui.global::<Skin>().get_high_contrast() != high {
+                ui.invoke_settings_changed();
+            }
+        }
+        SystemAppearance::Accent((r, g, b)) => {
+            ui.global::<Skin>()
+                .set_system_accent(Color::from_rgb_u8(r, g, b));
+        }
+    });
+}
+
+/// Follows the desktop's color scheme and accent color: applies the current values, then
+/// every `SettingChanged` from the Settings portal.
+fn spawn_appearance_watcher(ui_weak: slint::Weak<AppWindow>) {
+    thread::spawn(move || {
+        if let Some(rgb) = kde_accent_color() {
+            apply_system_appearance(&ui_weak, SystemAppearance::Accent(rgb));
+        }
+        if let Err(err) = watch_system_appearance(&ui_weak) {
+            eprintln!("{err}");
+        }
+    });
+}
+
+fn watch_system_appearance(ui_weak: &slint::Weak<AppWindow>) -> Result<(), String> {
+    let connection = ZbusConnection::session()
+        .map_err(|err| format!("appearance: session bus failed: {err}"))?;
+    let proxy = ZbusProxy::new(
+        &connection,
+        "org.freedesktop.portal.Desktop",
+        "/org/freedesktop/portal/desktop",
+        "org.freedesktop.portal.Settings",
+    )
+    .map_err(|err| format!("appearance: settings proxy failed: {err}"))?;
+    // Subscribe before reading so a change in between is not lost.
+    let changes = proxy
+        .receive_signal("SettingChanged")
+        .map_err(|err| format!("appearance: change stream failed: {err}"))?;
+
+    for key in ["color-scheme", "contrast"] {
+        if let Some(appearance) = read_appearance_setting(&proxy, key) {
+            apply_system_appearance(ui_weak, appearance);
+        }
+    }
+    // The portal's accent wins over kdeglobals once it has reported one.
+    let mut portal_accent = false;
+    if let Some(accent) = read_appearance_setting(&proxy, "accent-color") {
+        portal_accent = true;
+        apply_system_appearance(ui_weak, accent);
+    }
+
+    for message in changes {
+        let decoded = message.body().deserialize::<(String, String, OwnedValue)>();
+        let (namespace, key, value) = match decoded {
+            Ok(decoded) => decoded,
+            Err(err) => {
+                eprintln!("appearance: change decode failed: {err}");
+                continue;
+            }
+        };
+        if namespace == APPEARANCE_NAMESPACE {
+            if let Some(appearance) = appearance_from_setting(&key, &value) {
+                portal_accent |= matches!(appearance, SystemAppearance::Accent(_));
+                apply_system_appearance(ui_weak, appearance);
+            }
+        } else if namespace == KDE_GENERAL_NAMESPACE && key == "AccentColor" && !portal_accent {
+            if let Some(rgb) = value.downcast_ref::<&str>().ok().and_then(detect_color) {
+                apply_system_appearance(ui_weak, SystemAppearance::Accent(rgb));
+            }
+        }
+    }
+
+    Err("appearance: change stream ended".to_string())
+}
+
 fn pick_color_via_portal() -> Result<Option<(u8, u8, u8)>, String> {
     let connection =
         ZbusConnection::session().map_err(|err| format!("portal: session bus failed: {err}"))?;
@@ -1122,6 +5540,66 @@ fn pick_color_via_portal() -> Result<Option<(u8, u8, u8)>, String> {
     Ok(Some((to_u8(red), to_u8(green), to_u8(blue))))
 }
 
+/// Asks the FileChooser portal for an image; `None` when the dialog was dismissed.
+fn choose_image_via_portal() -> Result<Option<PathBuf>, String> {
+    let connection =
+        ZbusConnection::session().map_err(|err| format!("portal: session bus failed: {err}"))?;
+
+    let chooser_proxy = ZbusProxy::new(
+        &connection,
+        "org.freedesktop.portal.Desktop",
+        "/org/freedesktop/portal/desktop",
+        "org.freedesktop.portal.FileChooser",
+    )
+    .map_err(|err| format!("portal: file chooser proxy failed: {err}"))?;
+
+    let handle_token = next_portal_handle_token("archtoys_open");
+    let filter_name = tr("Images");
+    let filters = vec![(
+        filter_name.as_str(),
+        vec![
+            (1u32, "image/png"),
+            (1u32, "image/jpeg"),
+            (1u32, "image/webp"),
+        ],
+    )];
+    let mut options: HashMap<&str, Value<'_>> = HashMap::new();
+    options.insert("handle_token", Value::from(handle_token.as_str()));
+    options.insert("filters", Value::from(filters));
+
+    let title = tr("Open Image");
+    let reply = chooser_proxy
+        .call_method("OpenFile", &("", title.as_str(), &options))
+        .map_err(|err| format!("portal: OpenFile call failed: {err}"))?;
+
+    let (handle_path,): (OwnedObjectPath,) = reply
+        .body()
+        .deserialize()
+        .map_err(|err| format!("portal: OpenFile reply decode failed: {err}"))?;
+
+    let (response_code, results) = wait_for_portal_response(&connection, &handle_path)?;
+    if response_code == 1 || response_code == 2 {
+        return Ok(None);
+    }
+    if response_code != 0 {
+        return Err(format!(
+            "portal: OpenFile request rejected with code {response_code}"
+        ));
+    }
+
+    let uris: Vec<String> = results
+        .get("uris")
+        .ok_or_else(|| "portal: response did not include uris".to_string())?
+        .clone()
+        .try_into()
+        .map_err(|_| "portal: uris type conversion failed".to_string())?;
+
+    Ok(uris
+        .first()
+        .and_then(|uri| url::Url::parse(uri).ok())
+        .and_then(|uri| uri.to_file_path().ok()))
+}
+
 fn pick_color_via_kwin() -> Result<Option<(u8, u8, u8)>, String> {
     let connection =
         ZbusConnection::session().map_err(|err| format!("kwin: session bus failed: {err}"))?;
@@ -1168,11 +5646,20 @@ fn pick_color_via_kwin() -> Result<Option<(u8, u8, u8)>, String> {
     Ok(Some((red, green, blue)))
 }
 
-fn start_wayland_picker(
-    ui_weak: slint::Weak<AppWindow>,
-    history_store: Arc<Mutex<Vec<(u8, u8, u8)>>>,
-    context: PickerContext,
-) {
+fn start_wayland_picker(
+    ui_weak: slint::Weak<AppWindow>,
+    history_store: HistoryStore,
+    context: PickerContext,
+) {
+    if matches!(
+        context.outcome,
+        PickOutcome::Palette | PickOutcome::Watch | PickOutcome::Gradient
+    ) {
+        eprintln!("wayland picker: regions, gradients and point watching need an X11 session");
+        finish_picker(ui_weak, &history_store, context, false);
+        return;
+    }
+
     thread::spawn(move || {
         let result = match pick_color_via_kwin() {
             Ok(picked) => Ok(picked),
@@ -1188,33 +5675,204 @@ fn start_wayland_picker(
                 let ui_weak2 = ui_weak.clone();
                 let invoke_result = slint::invoke_from_event_loop(move || {
                     if let Some(ui) = ui_weak2.upgrade() {
-                        apply_selected_color(&ui, &history_store2, r, g, b);
+                        apply_selected_color(
+                            &ui,
+                            &history_store2,
+                            context.outcome,
+                            srgb_from_rgb8((r, g, b)),
+                        );
                     }
                 });
 
                 if let Err(err) = invoke_result {
                     eprintln!("wayland picker: invoke_from_event_loop failed: {err:?}");
-                    finish_picker(ui_weak, context, false);
+                    finish_picker(ui_weak, &history_store, context, false);
                     return;
                 }
 
-                finish_picker(ui_weak, context, true);
+                finish_picker(ui_weak, &history_store, context, true);
             }
             Ok(None) => {
                 PICKER_CANCELLED.store(true, Ordering::SeqCst);
-                finish_picker(ui_weak, context, false);
+                finish_picker(ui_weak, &history_store, context, false);
             }
             Err(err) => {
                 eprintln!("wayland picker: {err}");
-                finish_picker(ui_weak, context, false);
+                finish_picker(ui_weak, &history_store, context, false);
+            }
+        }
+    });
+}
+
+fn with_image_picker<R>(f: impl FnOnce(&mut Option<ImagePickerWindow>) -> R) -> R {
+    IMAGE_PICKER.with(|slot| {
+        let mut window = slot.borrow_mut();
+        f(&mut window)
+    })
+}
+
+fn release_image_picker() {
+    with_image_picker(|slot| {
+        if let Some(window) = slot.take() {
+            window.hide().ok();
+        }
+    });
+}
+
+/// Lets the user choose an image through the FileChooser portal, then opens it for picking.
+fn choose_image_file(ui: &AppWindow, history_store: &HistoryStore) {
+    let ui_weak = ui.as_weak();
+    let history_store = history_store.clone();
+    thread::spawn(move || match choose_image_via_portal() {
+        Ok(Some(path)) => open_image_picker(ui_weak, history_store, path),
+        Ok(None) => {}
+        Err(err) => eprintln!("image picker: {err}"),
+    });
+}
+
+/// Decodes `path` off the event loop and shows it in the image picker window.
+fn open_image_picker(ui_weak: slint::Weak<AppWindow>, history_store: HistoryStore, path: PathBuf) {
+    thread::spawn(move || {
+        let image = match image::open(&path) {
+            Ok(image) => image.to_rgba8(),
+            Err(err) => {
+                eprintln!("image picker: could not open {}: {err}", path.display());
+                return;
+            }
+        };
+
+        let _ = ui_weak.upgrade_in_event_loop(move |ui| {
+            if let Err(err) = show_image_picker(&ui, &history_store, &path, image) {
+                eprintln!("image picker: failed to create window: {err:?}");
+            }
+        });
+    });
+}
+
+fn show_image_picker(
+    ui: &AppWindow,
+    history_store: &HistoryStore,
+    path: &Path,
+    image: RgbaImage,
+) -> Result<(), slint::PlatformError> {
+    release_image_picker();
+
+    let window = ImagePickerWindow::new()?;
+    let buffer = SharedPixelBuffer::<Rgba8Pixel>::clone_from_slice(
+        image.as_raw(),
+        image.width(),
+        image.height(),
+    );
+    window.set_source_image(slint::Image::from_rgba8(buffer));
+    window.set_file_name(
+        path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+            .into(),
+    );
+
+    let cfg = synced_config();
+    let loupe_pixels = loupe_pixels(&cfg);
+    let show_loupe = cfg.overlay_loupe;
+    window.set_loupe_pixels(loupe_pixels as i32);
+    window.set_show_loupe(show_loupe);
+
+    let image = Rc::new(image);
+    let sample = move |x: i32, y: i32| -> Option<(u8, u8, u8)> {
+        let (x, y) = (u32::try_from(x).ok()?, u32::try_from(y).ok()?);
+        (x < image.width() && y < image.height()).then(|| {
+            let Rgba([r, g, b, _]) = *image.get_pixel(x, y);
+            (r, g, b)
+        })
+    };
+    let sample = Rc::new(sample);
+
+    let window_weak = window.as_weak();
+    let move_sample = sample.clone();
+    window.on_pointer_moved(move |x, y| {
+        let Some(window) = window_weak.upgrade() else {
+            return;
+        };
+        match move_sample(x, y) {
+            Some((r, g, b)) => {
+                window.set_preview_color(Color::from_rgb_u8(r, g, b));
+                window.set_preview_hex(format_hex(r, g, b).into());
+                if show_loupe {
+                    let radius = loupe_pixels as i32 / 2;
+                    let loupe: Vec<_> = (-radius..=radius)
+                        .flat_map(|dy| (-radius..=radius).map(move |dx| (x + dx, y + dy)))
+                        .map(|(px, py)| move_sample(px, py).unwrap_or((0, 0, 0)))
+                        .collect();
+                    window.set_loupe(loupe_image(&loupe, loupe_pixels));
+                }
+                window.set_preview_visible(true);
             }
+            None => window.set_preview_visible(false),
         }
     });
+
+    let ui_weak = ui.as_weak();
+    let history_store = history_store.clone();
+    window.on_pick_at(move |x, y| {
+        let Some((r, g, b)) = sample(x, y) else {
+            return;
+        };
+        let ui_weak = ui_weak.clone();
+        let history_store = history_store.clone();
+        // Closing the picker from inside its own callback is deferred to the event loop.
+        slint::Timer::single_shot(Duration::ZERO, move || {
+            release_image_picker();
+            if let Some(ui) = ui_weak.upgrade() {
+                apply_selected_color(
+                    &ui,
+                    &history_store,
+                    PickOutcome::Settings,
+                    srgb_from_rgb8((r, g, b)),
+                );
+            }
+        });
+    });
+
+    window.on_cancel_pick(|| {
+        slint::Timer::single_shot(Duration::ZERO, release_image_picker);
+    });
+    window.window().on_close_requested(|| {
+        slint::Timer::single_shot(Duration::ZERO, release_image_picker);
+        slint::CloseRequestResponse::HideWindow
+    });
+
+    window.show()?;
+    window.invoke_fit_image();
+    with_image_picker(|slot| *slot = Some(window));
+    Ok(())
+}
+
+fn begin_pick(
+    ui: &AppWindow,
+    history_store: &HistoryStore,
+    source: PickerSource,
+    outcome: PickOutcome,
+) {
+    let was_visible = ui.window().is_visible();
+    if ui.get_setting_minimize() {
+        ui.window().hide().ok();
+    }
+
+    start_picker(
+        ui.as_weak(),
+        history_store.clone(),
+        PickerContext {
+            source,
+            was_visible_before_trigger: was_visible,
+            outcome,
+            color_space: configured_color_space(ui),
+        },
+    );
 }
 
 fn start_picker(
     ui_weak: slint::Weak<AppWindow>,
-    history_store: Arc<Mutex<Vec<(u8, u8, u8)>>>,
+    history_store: HistoryStore,
     context: PickerContext,
 ) {
     if PICKER_ACTIVE
@@ -1234,8 +5892,74 @@ fn start_picker(
     }
 }
 
+/// Directory holding `<lang>/LC_MESSAGES/color-picker.mo`: `share/locale` next to the
+/// binary for package and AppImage installs, else the source tree's `lang/`.
+fn locale_dir() -> PathBuf {
+    let installed = std::env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.parent()?.join("../share/locale")));
+    match installed {
+        Some(dir) if dir.is_dir() => dir,
+        _ => PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/lang")),
+    }
+}
+
+/// Translates `text` through the same gettext catalog as the `@tr()` strings in the UI.
+fn tr(text: &str) -> String {
+    gettextrs::dgettext(env!("CARGO_PKG_NAME"), text)
+}
+
+/// Translates `text` and fills its `{}` placeholders in order, like `@tr()` does.
+fn tr_format(text: &str, args: &[&dyn std::fmt::Display]) -> String {
+    let mut args = args.iter();
+    let mut result = String::new();
+    for (index, piece) in tr(text).split("{}").enumerate() {
+        if index > 0 {
+            if let Some(arg) = args.next() {
+                result.push_str(&arg.to_string());
+            }
+        }
+        result.push_str(piece);
+    }
+    result
+}
+
+/// Languages that write decimals with a comma. Only readouts use this; value rows, copy
+/// formats and CSV keep the point so CSS and spreadsheets can read them.
+const COMMA_DECIMAL_LANGUAGES: &[&str] = &[
+    "be", "bg", "ca", "cs", "da", "de", "el", "es", "et", "eu", "fi", "fr", "gl", "hr", "hu", "id",
+    "is", "it", "kk", "lt", "lv", "nb", "nl", "nn", "pl", "pt", "ro", "ru", "sk", "sl", "sr", "sv",
+    "tr", "uk", "vi",
+];
+
+/// Replaces the decimal point of a formatted readout with the `LC_NUMERIC` separator.
+fn localize_decimal(text: String) -> String {
+    static COMMA: OnceLock<bool> = OnceLock::new();
+    let comma = *COMMA.get_or_init(|| {
+        let locale = ["LC_ALL", "LC_NUMERIC", "LANG"]
+            .into_iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+        let language = locale.split(['_', '.', '@']).next().unwrap_or_default();
+        COMMA_DECIMAL_LANGUAGES.contains(&language)
+    });
+    if comma {
+        text.replace('.', ",")
+    } else {
+        text
+    }
+}
+
 fn main() -> Result<(), slint::PlatformError> {
+    // Also calls setlocale, so LANG, LC_ALL and LC_MESSAGES pick the catalog.
+    slint::init_translations!(locale_dir());
+
     let start_hidden = std::env::args().any(|arg| arg == "--start-hidden");
+    let open_image_arg = std::env::args()
+        .skip_while(|arg| arg != "--open-image")
+        .nth(1)
+        .map(PathBuf::from);
 
     let ui = AppWindow::new()?;
     apply_native_window_constraints(&ui);
@@ -1244,33 +5968,64 @@ fn main() -> Result<(), slint::PlatformError> {
     }
     let ui_handle = ui.as_weak();
 
+    let history_store: HistoryStore =
+        Arc::new(Mutex::new(vec![(203u8, 182u8, 172u8), (85u8, 85u8, 85u8)]));
+
     let tray = AppTray {
         ui: ui_handle.clone(),
+        history_store: history_store.clone(),
+        recent: vec![],
+        current: srgb_from_rgb8((203, 182, 172)),
+        swatch_icon: false,
+        autocopy: false,
+        dark_mode: false,
+        copy_format: ColorField::Hex,
     };
-    let _tray_handle = match tray.spawn() {
-        Ok(handle) => Some(handle),
-        Err(err) => {
-            eprintln!("tray: failed to spawn: {err:?}");
-            None
-        }
-    };
-
-    let history_store: Arc<Mutex<Vec<(u8, u8, u8)>>> =
-        Arc::new(Mutex::new(vec![(203u8, 182u8, 172u8), (85u8, 85u8, 85u8)]));
+    match tray.spawn() {
+        Ok(handle) => TRAY_HANDLE.with(|slot| *slot.borrow_mut() = Some(handle)),
+        Err(err) => eprintln!("tray: failed to spawn: {err:?}"),
+    }
 
-    if let Some(cfg) = load_config() {
-        apply_config(&ui, &history_store, &cfg);
+    let loaded_config = load_config();
+    if let Some(cfg) = &loaded_config {
+        apply_config(&ui, &history_store, cfg);
     }
+    apply_user_theme(&ui);
+    ui.global::<ColorNames>()
+        .on_spoken(|color| spoken_color(color).into());
+    ui.global::<ColorNames>().on_description(|color| {
+        if color.alpha() == 0 {
+            return SharedString::new();
+        }
+        let rgb = (color.red(), color.green(), color.blue());
+        describe_color(srgb_from_rgb8(rgb)).into()
+    });
     if ui.get_setting_hotkey().trim().is_empty() {
         ui.set_setting_hotkey(DEFAULT_HOTKEY_TEXT.into());
     }
     sync_autostart_entry(ui.get_setting_autostart());
 
-    let hotkey_manager = match GlobalHotKeyManager::new() {
-        Ok(manager) => Some(Arc::new(manager)),
-        Err(err) => {
-            eprintln!("hotkey: manager init failed: {err}");
-            None
+    let portal_shortcuts = if detect_session_type() == SessionType::Wayland {
+        match PortalShortcuts::create_session() {
+            Ok(portal) => Some(portal),
+            Err(err) => {
+                eprintln!("hotkey: {err}; falling back to X11 key grabs");
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let hotkey_manager = if portal_shortcuts.is_some() {
+        None
+    } else {
+        match GlobalHotKeyManager::new() {
+            Ok(manager) => Some(Arc::new(manager)),
+            Err(err) => {
+                eprintln!("hotkey: manager init failed: {err}");
+                None
+            }
         }
     };
 
@@ -1306,41 +6061,57 @@ fn main() -> Result<(), slint::PlatformError> {
 
     ui.set_setting_hotkey(registered_hotkey_text.clone().into());
 
-    let active_hotkey = Arc::new(Mutex::new(registered_hotkey));
-    let active_hotkey_id = Arc::new(Mutex::new(registered_hotkey.id()));
-    let active_hotkey_text = Arc::new(Mutex::new(registered_hotkey_text));
+    let hotkeys = HotkeyState {
+        manager: hotkey_manager.clone(),
+        active: Arc::new(Mutex::new(registered_hotkey)),
+        active_id: Arc::new(Mutex::new(registered_hotkey.id())),
+        active_text: Arc::new(Mutex::new(registered_hotkey_text)),
+        bindings: Arc::new(Mutex::new(vec![])),
+        portal: portal_shortcuts,
+    };
+    if let Some(cfg) = &loaded_config {
+        register_hotkey_bindings(&hotkeys, &cfg.hotkey_bindings);
+    }
+    rebind_portal_shortcuts(&ui, &hotkeys);
 
     sync_history_model(&ui, &history_store);
     update_ui_colors(&ui, 203, 182, 172);
+    record_undo_step(&ui, &history_store);
 
     if hotkey_manager.is_some() {
         let hk_ui = ui_handle.clone();
         let hk_history = history_store.clone();
-        let hk_active_id = active_hotkey_id.clone();
+        let hk_state = hotkeys.clone();
         thread::spawn(move || {
             let receiver = global_hotkey::GlobalHotKeyEvent::receiver();
             while let Ok(event) = receiver.recv() {
-                let current_hotkey_id = *hk_active_id.lock().unwrap();
-                if event.id == current_hotkey_id {
-                    let ui_for_pick = hk_ui.clone();
-                    let history_for_pick = hk_history.clone();
-
-                    let _ = ui_for_pick.upgrade_in_event_loop(move |ui| {
-                        let was_visible = ui.window().is_visible();
-                        if ui.get_setting_minimize() {
-                            ui.window().hide().ok();
-                        }
+                let Some(action) = hotkey_action_for_id(&hk_state, event.id) else {
+                    continue;
+                };
+                let history_for_action = hk_history.clone();
+                let _ = hk_ui.upgrade_in_event_loop(move |ui| {
+                    run_hotkey_action(&ui, &history_for_action, action);
+                });
+            }
+        });
+    }
 
-                        start_picker(
-                            ui.as_weak(),
-                            history_for_pick,
-                            PickerContext {
-                                source: PickerSource::Hotkey,
-                                was_visible_before_trigger: was_visible,
-                            },
-                        );
-                    });
-                }
+    if let Some(portal) = hotkeys.portal.clone() {
+        let hk_ui = ui_handle.clone();
+        let hk_history = history_store.clone();
+        let hk_state = hotkeys.clone();
+        thread::spawn(move || {
+            let result = portal.listen(|shortcut_id| {
+                let Some(action) = portal_action_for_id(&hk_state, shortcut_id) else {
+                    return;
+                };
+                let history_for_action = hk_history.clone();
+                let _ = hk_ui.upgrade_in_event_loop(move |ui| {
+                    run_hotkey_action(&ui, &history_for_action, action);
+                });
+            });
+            if let Err(err) = result {
+                eprintln!("hotkey: {err}");
             }
         });
     }
@@ -1349,17 +6120,20 @@ fn main() -> Result<(), slint::PlatformError> {
     let settings_history = history_store.clone();
     ui.on_settings_changed(move || {
         if let Some(ui) = settings_ui.upgrade() {
+            apply_followed_color_scheme(&ui);
             persist_config(&ui, &settings_history);
             sync_autostart_entry(ui.get_setting_autostart());
+            sync_tray(&ui, &settings_history);
+            send_clipboard_request(ClipboardRequest::Style(configured_clipboard_style(&ui)));
         }
     });
 
+    spawn_config_watcher(ui_handle.clone(), history_store.clone(), hotkeys.clone());
+    spawn_appearance_watcher(ui_handle.clone());
+
     let hotkey_ui = ui_handle.clone();
     let hotkey_history = history_store.clone();
-    let hotkey_manager_apply = hotkey_manager.clone();
-    let hotkey_active = active_hotkey.clone();
-    let hotkey_active_id = active_hotkey_id.clone();
-    let hotkey_active_text = active_hotkey_text.clone();
+    let hotkey_state = hotkeys.clone();
     ui.on_hotkey_captured(move |key_text, ctrl, alt, shift, meta| {
         let Some(ui) = hotkey_ui.upgrade() else {
             return;
@@ -1370,72 +6144,109 @@ fn main() -> Result<(), slint::PlatformError> {
                 Ok(candidate) => candidate,
                 Err(err) => {
                     eprintln!("hotkey: {err}");
-                    let current_text = hotkey_active_text.lock().unwrap().clone();
+                    let current_text = hotkey_state.active_text.lock().unwrap().clone();
                     ui.set_setting_hotkey(current_text.into());
                     return;
                 }
             };
 
-        let (new_hotkey, normalized_text) = match parse_hotkey_text(&candidate) {
-            Ok(parsed) => parsed,
-            Err(err) => {
-                eprintln!("hotkey: {err}");
-                let current_text = hotkey_active_text.lock().unwrap().clone();
-                ui.set_setting_hotkey(current_text.into());
-                return;
-            }
-        };
+        apply_hotkey_candidate(&ui, &hotkey_history, &hotkey_state, &candidate);
+    });
 
-        let old_hotkey = *hotkey_active.lock().unwrap();
-        if new_hotkey.id() == old_hotkey.id() {
-            ui.set_setting_hotkey(normalized_text.clone().into());
-            *hotkey_active_text.lock().unwrap() = normalized_text;
-            persist_config(&ui, &hotkey_history);
-            return;
+    let pick_ui = ui_handle.clone();
+    let pick_history = history_store.clone();
+    ui.on_pick_color(move || {
+        if let Some(ui) = pick_ui.upgrade() {
+            begin_pick(
+                &ui,
+                &pick_history,
+                PickerSource::Button,
+                PickOutcome::Settings,
+            );
         }
+    });
 
-        if let Some(manager) = hotkey_manager_apply.as_ref() {
-            let _ = manager.unregister(old_hotkey);
-            if let Err(err) = manager.register(new_hotkey) {
-                eprintln!("hotkey: failed to register `{}`: {err}", normalized_text);
-                let _ = manager.register(old_hotkey);
-                let previous_text = hotkey_active_text.lock().unwrap().clone();
-                ui.set_setting_hotkey(previous_text.into());
-                return;
-            }
+    let paste_ui = ui_handle.clone();
+    let paste_history = history_store.clone();
+    ui.on_paste_color(move || {
+        if let Some(ui) = paste_ui.upgrade() {
+            paste_color_from_clipboard(&ui, &paste_history);
+        }
+    });
+
+    let open_image_ui = ui_handle.clone();
+    let open_image_history = history_store.clone();
+    ui.on_open_image(move || {
+        if let Some(ui) = open_image_ui.upgrade() {
+            choose_image_file(&ui, &open_image_history);
+        }
+    });
+
+    ui.on_copy_to_clipboard(move |text| {
+        copy_color_async(text.to_string(), srgb_to_rgb8(current_color()));
+    });
+
+    let copy_row_ui = ui_handle.clone();
+    ui.on_copy_value_row(move |text| {
+        let Some(ui) = copy_row_ui.upgrade() else {
+            return;
+        };
+        if configured_clipboard_style(&ui) == ClipboardStyle::Image {
+            copy_text_async(text.to_string());
         } else {
-            eprintln!("hotkey: global manager unavailable on this session; saving only");
+            copy_color_async(text.to_string(), srgb_to_rgb8(current_color()));
         }
+    });
 
-        *hotkey_active.lock().unwrap() = new_hotkey;
-        *hotkey_active_id.lock().unwrap() = new_hotkey.id();
-        *hotkey_active_text.lock().unwrap() = normalized_text.clone();
-        ui.set_setting_hotkey(normalized_text.into());
-        persist_config(&ui, &hotkey_history);
+    let palette_click_ui = ui_handle.clone();
+    let palette_click_history = history_store.clone();
+    ui.on_palette_clicked(move |index| {
+        if let Some(ui) = palette_click_ui.upgrade() {
+            if let Some(color) = ui.get_palette_model().row_data(index as usize) {
+                update_ui_colors(&ui, color.red(), color.green(), color.blue());
+                record_undo_step(&ui, &palette_click_history);
+            }
+        }
     });
 
-    let pick_ui = ui_handle.clone();
-    let pick_history = history_store.clone();
-    ui.on_pick_color(move || {
-        if let Some(ui) = pick_ui.upgrade() {
-            let was_visible = ui.window().is_visible();
-            if ui.get_setting_minimize() {
-                ui.window().hide().ok();
+    let gradient_click_ui = ui_handle.clone();
+    let gradient_click_history = history_store.clone();
+    ui.on_gradient_clicked(move |index| {
+        if let Some(ui) = gradient_click_ui.upgrade() {
+            if let Some(color) = ui.get_gradient_model().row_data(index as usize) {
+                update_ui_colors(&ui, color.red(), color.green(), color.blue());
+                record_undo_step(&ui, &gradient_click_history);
             }
+        }
+    });
 
-            start_picker(
-                ui.as_weak(),
-                pick_history.clone(),
-                PickerContext {
-                    source: PickerSource::Button,
-                    was_visible_before_trigger: was_visible,
-                },
-            );
+    let gradient_css_ui = ui_handle.clone();
+    ui.on_gradient_copy_css(move || {
+        if let Some(ui) = gradient_css_ui.upgrade() {
+            let stops = reduce_gradient_stops(&gradient_samples(&ui));
+            if !stops.is_empty() {
+                copy_text_async(css_linear_gradient(&stops, ui.get_gradient_angle()));
+            }
         }
     });
 
-    ui.on_copy_to_clipboard(move |text| {
-        copy_text_async(text.to_string());
+    let gradient_palette_ui = ui_handle.clone();
+    let gradient_palette_history = history_store.clone();
+    ui.on_gradient_to_palette(move || {
+        if let Some(ui) = gradient_palette_ui.upgrade() {
+            let mut colors: Vec<(u8, u8, u8)> = ui
+                .get_palette_model()
+                .iter()
+                .map(|color| (color.red(), color.green(), color.blue()))
+                .collect();
+            for (_, rgb) in reduce_gradient_stops(&gradient_samples(&ui)) {
+                if !colors.contains(&rgb) {
+                    colors.push(rgb);
+                }
+            }
+            set_palette_model(&ui, colors.into_iter());
+            persist_config(&ui, &gradient_palette_history);
+        }
     });
 
     let history_click_ui = ui_handle.clone();
@@ -1443,8 +6254,11 @@ fn main() -> Result<(), slint::PlatformError> {
     ui.on_history_clicked(move |index| {
         if let Some(ui) = history_click_ui.upgrade() {
             let guard = history_click_store.lock().unwrap();
-            if let Some((r, g, b)) = guard.get(index as usize) {
-                update_ui_colors(&ui, *r, *g, *b);
+            if let Some(&(r, g, b)) = guard.get(index as usize) {
+                drop(guard);
+                update_ui_colors(&ui, r, g, b);
+                ui.set_history_selected(index);
+                record_undo_step(&ui, &history_click_store);
             }
         }
     });
@@ -1455,8 +6269,7 @@ fn main() -> Result<(), slint::PlatformError> {
         let Some(ui) = clear_ui.upgrade() else {
             return;
         };
-        let current = ui.get_current_color();
-        let keep_rgb = (current.red(), current.green(), current.blue());
+        let keep_rgb = srgb_to_rgb8(current_color());
 
         {
             let mut guard = clear_history.lock().unwrap();
@@ -1471,18 +6284,156 @@ fn main() -> Result<(), slint::PlatformError> {
     let shade_history = history_store.clone();
     ui.on_shade_clicked(move |factor| {
         if let Some(ui) = shade_ui.upgrade() {
-            let current = ui.get_current_color();
-            let r = (current.red() as f32 * factor).clamp(0.0, 255.0) as u8;
-            let g = (current.green() as f32 * factor).clamp(0.0, 255.0) as u8;
-            let b = (current.blue() as f32 * factor).clamp(0.0, 255.0) as u8;
+            let current = current_color();
+            let color = clamp_srgb(Srgb::new(
+                current.red * factor,
+                current.green * factor,
+                current.blue * factor,
+            ));
 
-            push_history(&shade_history, (r, g, b));
+            push_history(&shade_history, srgb_to_rgb8(color));
             sync_history_model(&ui, &shade_history);
-            update_ui_colors(&ui, r, g, b);
+            update_ui_colors_precise(&ui, color);
             persist_config(&ui, &shade_history);
         }
     });
 
+    let mixer_ui = ui_handle.clone();
+    ui.on_mixer_changed(move || {
+        if let Some(ui) = mixer_ui.upgrade() {
+            update_mixer(&ui);
+        }
+    });
+
+    let mix_pick_ui = ui_handle.clone();
+    let mix_pick_history = history_store.clone();
+    ui.on_mix_pick(move |color| {
+        if let Some(ui) = mix_pick_ui.upgrade() {
+            let (r, g, b) = (color.red(), color.green(), color.blue());
+            push_history(&mix_pick_history, (r, g, b));
+            sync_history_model(&ui, &mix_pick_history);
+            update_ui_colors(&ui, r, g, b);
+            persist_config(&ui, &mix_pick_history);
+        }
+    });
+
+    let editor_plane_ui = ui_handle.clone();
+    ui.on_editor_plane_moved(move |x, y| {
+        if let Some(ui) = editor_plane_ui.upgrade() {
+            ui.set_editor_x(x.clamp(0.0, 1.0));
+            ui.set_editor_y(y.clamp(0.0, 1.0));
+            preview_editor_plane(&ui);
+        }
+    });
+
+    let editor_hue_ui = ui_handle.clone();
+    ui.on_editor_hue_moved(move || {
+        if let Some(ui) = editor_hue_ui.upgrade() {
+            refresh_editor_plane(&ui);
+            preview_editor_plane(&ui);
+        }
+    });
+
+    let editor_channel_ui = ui_handle.clone();
+    ui.on_editor_channel_moved(move || {
+        if let Some(ui) = editor_channel_ui.upgrade() {
+            preview_editor_channels(&ui);
+        }
+    });
+
+    let editor_mode_ui = ui_handle.clone();
+    ui.on_editor_mode_changed(move || {
+        if let Some(ui) = editor_mode_ui.upgrade() {
+            sync_editor(&ui, current_color());
+            refresh_editor_plane(&ui);
+        }
+    });
+
+    let editor_release_ui = ui_handle.clone();
+    let editor_release_history = history_store.clone();
+    ui.on_editor_released(move || {
+        if let Some(ui) = editor_release_ui.upgrade() {
+            let current = current_color();
+            push_history(&editor_release_history, srgb_to_rgb8(current));
+            sync_history_model(&ui, &editor_release_history);
+            update_tray(move |tray| tray.current = current);
+            persist_config(&ui, &editor_release_history);
+        }
+    });
+
+    let compare_log: Arc<Mutex<Vec<CompareEntry>>> = Arc::new(Mutex::new(Vec::new()));
+
+    let compare_current_ui = ui_handle.clone();
+    ui.on_compare_use_current(move || {
+        if let Some(ui) = compare_current_ui.upgrade() {
+            set_compare_reference(&ui, srgb_to_rgb8(current_color()));
+        }
+    });
+
+    let compare_entered_ui = ui_handle.clone();
+    ui.on_compare_reference_entered(move |text| {
+        if let Some(ui) = compare_entered_ui.upgrade() {
+            match detect_color(&text) {
+                Some(rgb) => set_compare_reference(&ui, rgb),
+                None => ui.set_compare_summary(tr_format("Not a color: {}", &[&text]).into()),
+            }
+        }
+    });
+
+    let compare_pick_ui = ui_handle.clone();
+    let compare_pick_history = history_store.clone();
+    ui.on_compare_pick_reference(move || {
+        if let Some(ui) = compare_pick_ui.upgrade() {
+            begin_pick(
+                &ui,
+                &compare_pick_history,
+                PickerSource::Button,
+                PickOutcome::Reference,
+            );
+        }
+    });
+
+    let compare_tolerance_ui = ui_handle.clone();
+    let compare_tolerance_history = history_store.clone();
+    ui.on_compare_tolerance_entered(move |text| {
+        if let Some(ui) = compare_tolerance_ui.upgrade() {
+            match text.trim().parse::<f32>() {
+                Ok(tolerance) if tolerance.is_finite() && tolerance >= 0.0 => {
+                    ui.set_compare_tolerance(tolerance);
+                    update_compare(&ui);
+                    persist_config(&ui, &compare_tolerance_history);
+                }
+                _ => eprintln!("compare: invalid tolerance {text:?}"),
+            }
+        }
+    });
+
+    let compare_log_ui = ui_handle.clone();
+    let compare_log_entries = compare_log.clone();
+    ui.on_compare_log(move || {
+        if let Some(ui) = compare_log_ui.upgrade() {
+            if let Some(entry) = current_compare_entry(&ui) {
+                let mut entries = compare_log_entries.lock().unwrap();
+                entries.push(entry);
+                ui.set_compare_log_count(entries.len() as i32);
+            }
+        }
+    });
+
+    let compare_export_ui = ui_handle.clone();
+    let compare_export_entries = compare_log.clone();
+    ui.on_compare_export(move || {
+        if let Some(ui) = compare_export_ui.upgrade() {
+            let entries = compare_export_entries.lock().unwrap().clone();
+            match export_compare_csv(&entries) {
+                Ok(path) => {
+                    ui.set_compare_summary(tr_format("Saved {}", &[&path.display()]).into())
+                }
+                Err(err) => eprintln!("compare: CSV export failed: {err}"),
+            }
+        }
+    });
+
     let edited_ui = ui_handle.clone();
     ui.on_value_edited(move |type_str, value| {
         let Some(field) = ColorField::from_ui_label(&type_str.to_string()) else {
@@ -1490,8 +6441,11 @@ fn main() -> Result<(), slint::PlatformError> {
         };
 
         if let Some(ui) = edited_ui.upgrade() {
-            if let Some((r, g, b)) = parse_color(field, &value.to_string()) {
-                update_ui_preview_except_field(&ui, field, r, g, b);
+            if let Some(color) = parse_color(field, &value.to_string()) {
+                update_ui_preview_except_field(&ui, Some(field), color);
+                sync_editor(&ui, color);
+                let note = srgb_gamut_note(&value, field.color_space()).unwrap_or_default();
+                ui.set_color_space_note(note.into());
             }
         }
     });
@@ -1504,10 +6458,13 @@ fn main() -> Result<(), slint::PlatformError> {
         };
 
         if let Some(ui) = accepted_ui.upgrade() {
-            if let Some((r, g, b)) = parse_color(field, &value.to_string()) {
-                push_history(&accepted_history, (r, g, b));
+            if let Some(color) = parse_color(field, &value.to_string()) {
+                push_history(&accepted_history, srgb_to_rgb8(color));
                 sync_history_model(&ui, &accepted_history);
-                update_ui_colors(&ui, r, g, b);
+                update_ui_colors_precise(&ui, color);
+                if let Some(note) = srgb_gamut_note(&value, field.color_space()) {
+                    ui.set_color_space_note(note.into());
+                }
                 persist_config(&ui, &accepted_history);
             } else {
                 update_ui_colors(&ui, 0, 0, 0);
@@ -1516,17 +6473,77 @@ fn main() -> Result<(), slint::PlatformError> {
     });
 
     let blurred_ui = ui_handle.clone();
+    let blurred_history = history_store.clone();
     ui.on_value_blurred(move |type_str, value| {
         let Some(field) = ColorField::from_ui_label(&type_str.to_string()) else {
             return;
         };
 
         if let Some(ui) = blurred_ui.upgrade() {
-            if let Some((r, g, b)) = parse_color(field, &value.to_string()) {
-                update_ui_colors(&ui, r, g, b);
+            if let Some(color) = parse_color(field, &value.to_string()) {
+                update_ui_colors_precise(&ui, color);
+                if let Some(note) = srgb_gamut_note(&value, field.color_space()) {
+                    ui.set_color_space_note(note.into());
+                }
             } else {
                 update_ui_colors(&ui, 0, 0, 0);
             }
+            record_undo_step(&ui, &blurred_history);
+        }
+    });
+
+    let undo_ui = ui_handle.clone();
+    let undo_history = history_store.clone();
+    ui.on_undo(move || {
+        if let Some(ui) = undo_ui.upgrade() {
+            step_undo(&ui, &undo_history, false);
+        }
+    });
+
+    let redo_ui = ui_handle.clone();
+    let redo_history = history_store.clone();
+    ui.on_redo(move || {
+        if let Some(ui) = redo_ui.upgrade() {
+            step_undo(&ui, &redo_history, true);
+        }
+    });
+
+    let history_step_ui = ui_handle.clone();
+    let history_step_store = history_store.clone();
+    ui.on_history_step(move |delta| {
+        if let Some(ui) = history_step_ui.upgrade() {
+            step_history_selection(&ui, &history_step_store, delta);
+        }
+    });
+
+    let history_remove_ui = ui_handle.clone();
+    let history_remove_store = history_store.clone();
+    ui.on_history_remove(move || {
+        if let Some(ui) = history_remove_ui.upgrade() {
+            remove_history_entry(&ui, &history_remove_store);
+        }
+    });
+
+    let command_matches: Arc<Mutex<Vec<AppCommand>>> = Arc::new(Mutex::new(Vec::new()));
+
+    let command_query_ui = ui_handle.clone();
+    let command_query_matches = command_matches.clone();
+    ui.on_command_query(move |query| {
+        if let Some(ui) = command_query_ui.upgrade() {
+            let (commands, items): (Vec<AppCommand>, Vec<CommandItem>) =
+                search_commands(&ui, &query).into_iter().unzip();
+            *command_query_matches.lock().unwrap() = commands;
+            ui.set_command_results(ModelRc::from(Rc::new(VecModel::from(items))));
+            ui.set_command_selected(0);
+        }
+    });
+
+    let command_run_ui = ui_handle.clone();
+    let command_run_history = history_store.clone();
+    ui.on_command_run(move |index| {
+        let command = command_matches.lock().unwrap().get(index as usize).copied();
+        if let (Some(ui), Some(command)) = (command_run_ui.upgrade(), command) {
+            run_app_command(&ui, &command_run_history, command);
         }
     });
 
@@ -1545,6 +6562,7 @@ fn main() -> Result<(), slint::PlatformError> {
             ui.set_close_confirm_open(false);
             persist_config(&ui, &close_history);
         }
+        shutdown_clipboard_owner();
         slint::quit_event_loop().ok();
         std::process::exit(0);
     });
@@ -1561,5 +6579,121 @@ fn main() -> Result<(), slint::PlatformError> {
         ui.show()?;
     }
 
-    slint::run_event_loop_until_quit()
+    if let Some(path) = open_image_arg {
+        open_image_picker(ui.as_weak(), history_store.clone(), path);
+    }
+
+    let result = slint::run_event_loop_until_quit();
+    shutdown_clipboard_owner();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pairs from Sharma, Wu and Dalal, "The CIEDE2000 Color-Difference Formula" (2005).
+    #[test]
+    fn delta_e2000_matches_sharma_reference_pairs() {
+        let pairs = [
+            ((50.0, 2.6772, -79.7751), (50.0, 0.0, -82.7485), 2.0425),
+            ((50.0, 3.1571, -77.2803), (50.0, 0.0, -82.7485), 2.8615),
+            ((50.0, 2.8361, -74.0200), (50.0, 0.0, -82.7485), 3.4412),
+            ((50.0, -1.3802, -84.2814), (50.0, 0.0, -82.7485), 1.0000),
+            ((50.0, 0.0, 0.0), (50.0, -1.0, 2.0), 2.3669),
+            ((50.0, 2.4900, -0.0010), (50.0, -2.4900, 0.0009), 7.1792),
+            ((50.0, 2.5, 0.0), (73.0, 25.0, -18.0), 27.1492),
+            ((50.0, 2.5, 0.0), (61.0, -5.0, 29.0), 22.8977),
+            (
+                (60.2574, -34.0099, 36.2677),
+                (60.4626, -34.1751, 39.4387),
+                1.2644,
+            ),
+            (
+                (63.0109, -31.0961, -5.8663),
+                (62.8187, -29.7946, -4.0864),
+                1.2630,
+            ),
+            (
+                (90.8027, -2.0831, 1.4410),
+                (91.1528, -1.6435, 0.0447),
+                1.4441,
+            ),
+            (
+                (90.9257, -0.5406, -0.9208),
+                (88.6381, -0.8985, -0.7239),
+                1.5381,
+            ),
+        ];
+        for ((l1, a1, b1), (l2, a2, b2), expected) in pairs {
+            let actual = delta_e2000(Lab::new(l1, a1, b1), Lab::new(l2, a2, b2));
+            assert!(
+                (actual - expected).abs() < 1e-3,
+                "ΔE2000 of ({l1}, {a1}, {b1}) and ({l2}, {a2}, {b2}) was {actual}, expected {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn tokenizer_reads_numbers_percentages_angles_and_none() {
+        assert_eq!(
+            tokenize_css_arguments("10 20% none / 0.5"),
+            Some(vec![
+                CssToken::Number(10.0),
+                CssToken::Percentage(20.0),
+                CssToken::None,
+                CssToken::Slash,
+                CssToken::Number(0.5),
+            ])
+        );
+        assert_eq!(
+            tokenize_css_arguments("120deg, 0.5turn"),
+            Some(vec![
+                CssToken::Angle(120.0),
+                CssToken::Comma,
+                CssToken::Angle(180.0),
+            ])
+        );
+        assert_eq!(tokenize_css_arguments("10 apples 30"), None);
+    }
+
+    #[test]
+    fn detect_color_reads_css_functions() {
+        assert_eq!(detect_color("rgb(255 0 none)"), Some((255, 0, 0)));
+        assert_eq!(detect_color("rgb(100%, 50%, 0%)"), Some((255, 128, 0)));
+        assert_eq!(detect_color("hsl(120deg 100% 50%)"), Some((0, 255, 0)));
+        assert_eq!(detect_color("color: #fff;"), Some((255, 255, 255)));
+
+        let (r, g, b) = detect_color("color(display-p3 1 0 0)").expect("P3 red is detected");
+        assert!(
+            r > 230 && g < 60 && b < 60,
+            "P3 red mapped to ({r}, {g}, {b})"
+        );
+    }
+
+    #[test]
+    fn detect_color_reads_8_and_16_bit_tuples() {
+        assert_eq!(detect_color("[10 20 30]"), Some((10, 20, 30)));
+        assert_eq!(detect_color("65535, 0, 0"), Some((255, 0, 0)));
+        assert_eq!(detect_color("65535, 32896, 200"), Some((255, 128, 1)));
+    }
+
+    #[test]
+    fn spoken_color_name_splits_css_names() {
+        assert_eq!(spoken_color_name("lightslategray"), "light slate gray");
+        assert_eq!(spoken_color_name("red"), "red");
+    }
+
+    #[test]
+    fn describe_color_names_lightness_saturation_and_family() {
+        let describe = |rgb| describe_color(srgb_from_rgb8(rgb));
+        assert_eq!(describe((255, 0, 0)), "vivid red, L 63%, near red");
+        assert_eq!(describe((128, 128, 128)), "gray, L 60%, near gray");
+        assert_eq!(describe((0, 0, 0)), "black, L 0%, near black");
+        assert_eq!(describe((255, 255, 255)), "white, L 100%, near white");
+        assert_eq!(
+            describe((139, 69, 19)),
+            "strong brown, L 47%, near saddle brown"
+        );
+    }
 }